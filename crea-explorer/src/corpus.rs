@@ -0,0 +1,325 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::parser::{self, Categoria, ElementoRecord, LemaRecord};
+
+/// A corpus that can provide elementos and lemas with their frequencies
+pub trait CorpusSource {
+    /// Name of this source for output
+    fn name(&self) -> String;
+
+    /// Map elemento -> record, keeping the most frequent lema and category of
+    /// repeated elementos but adding up their frequencies
+    fn elementos(&self) -> HashMap<String, ElementoRecord>;
+
+    /// Map lema -> record. By default the lemas are derived from the elementos
+    fn lemas(&self) -> HashMap<String, LemaRecord> {
+        lemas_from_elementos(&self.elementos())
+    }
+}
+
+/// The RAE's CREA, read from `crea_elementos.txt` and `crea_lemas.txt`
+pub struct Crea;
+
+impl CorpusSource for Crea {
+    fn name(&self) -> String {
+        "CREA".into()
+    }
+
+    fn elementos(&self) -> HashMap<String, ElementoRecord> {
+        parser::parse_elementos()
+    }
+
+    fn lemas(&self) -> HashMap<String, LemaRecord> {
+        parser::parse_lemas()
+    }
+}
+
+/// A CORPES XXI-style frequency list: a header row followed by rows of Forma,
+/// Lema, Categoría, Frecuencia absoluta, Frecuencia normalizada. Frequencies
+/// may use Spanish number formatting (`1.234`, `0,56`).
+pub struct Corpes {
+    pub path: PathBuf,
+}
+
+impl CorpusSource for Corpes {
+    fn name(&self) -> String {
+        format!("CORPES ({})", self.path.display())
+    }
+
+    fn elementos(&self) -> HashMap<String, ElementoRecord> {
+        println!("Parsing CORPES list {}...", self.path.display());
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(b'\t')
+            .flexible(true)
+            .from_path(&self.path)
+            .unwrap_or_else(|_| panic!("Unable to read {}", self.path.display()));
+        let records = reader
+            .records()
+            .filter_map(|record| record.ok())
+            .filter_map(|record| {
+                let forma = record.get(0)?.trim().to_string();
+                let lema = record.get(1)?.trim().to_string();
+                let categoria = Categoria::from_tag(record.get(2)?).unwrap_or(Categoria::U);
+                let freq = parse_spanish_usize(record.get(3)?)?;
+                let norm = parse_spanish_f64(record.get(4)?)?;
+                Some((forma, lema, categoria, freq, norm, norm))
+            });
+        let elementos = collect_elementos(records);
+        println!("Found {} elementos", elementos.len());
+        elementos
+    }
+}
+
+/// A generic headerless `form<TAB>lemma<TAB>pos<TAB>freq` list. The part of
+/// speech can be anything [Categoria::from_tag] understands, and the
+/// normalized frequency is computed per million words of the list.
+pub struct Tsv {
+    pub path: PathBuf,
+}
+
+impl CorpusSource for Tsv {
+    fn name(&self) -> String {
+        format!("TSV ({})", self.path.display())
+    }
+
+    fn elementos(&self) -> HashMap<String, ElementoRecord> {
+        println!("Parsing TSV list {}...", self.path.display());
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(b'\t')
+            .has_headers(false)
+            .flexible(true)
+            .from_path(&self.path)
+            .unwrap_or_else(|_| panic!("Unable to read {}", self.path.display()));
+        let records: Vec<(String, String, Categoria, usize)> = reader
+            .records()
+            .filter_map(|record| record.ok())
+            .filter_map(|record| {
+                let forma = record.get(0)?.trim().to_string();
+                let lema = record.get(1)?.trim().to_string();
+                let categoria = Categoria::from_tag(record.get(2)?).unwrap_or(Categoria::U);
+                let freq = record.get(3)?.trim().parse().ok()?;
+                Some((forma, lema, categoria, freq))
+            })
+            .collect();
+
+        let total: usize = records.iter().map(|(_, _, _, freq)| freq).sum();
+        let per_million = |freq: usize| freq as f64 * 1_000_000.0 / total.max(1) as f64;
+        let elementos = collect_elementos(records.into_iter().map(|(forma, lema, cat, freq)| {
+            (forma, lema, cat, freq, per_million(freq), per_million(freq))
+        }));
+        println!("Found {} elementos", elementos.len());
+        elementos
+    }
+}
+
+/// Command line description of a corpus source: `crea`, `corpes:<file>`, or
+/// `tsv:<file>`
+#[derive(Clone, Debug)]
+pub enum SourceSpec {
+    Crea,
+    Corpes(PathBuf),
+    Tsv(PathBuf),
+}
+
+impl SourceSpec {
+    pub fn source(&self) -> Box<dyn CorpusSource> {
+        match self {
+            SourceSpec::Crea => Box::new(Crea),
+            SourceSpec::Corpes(path) => Box::new(Corpes { path: path.clone() }),
+            SourceSpec::Tsv(path) => Box::new(Tsv { path: path.clone() }),
+        }
+    }
+}
+
+impl FromStr for SourceSpec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            None if s == "crea" => Ok(SourceSpec::Crea),
+            Some(("corpes", path)) => Ok(SourceSpec::Corpes(path.into())),
+            Some(("tsv", path)) => Ok(SourceSpec::Tsv(path.into())),
+            _ => Err(format!(
+                "unknown source {:?}, expected crea, corpes:<file>, or tsv:<file>",
+                s
+            )),
+        }
+    }
+}
+
+/// How to combine the frequencies of several sources
+#[derive(Clone, Copy, Debug, Default, clap::ValueEnum)]
+pub enum Merge {
+    /// Keep the highest frequency, so a word common in any source is common
+    #[default]
+    Max,
+    /// Keep the lowest frequency, so a word must be common in every source
+    Min,
+}
+
+/// Load and merge several sources into single elementos and lemas maps. Lemas
+/// and categories come from the first source that has the word.
+pub fn merge_sources(
+    sources: &[Box<dyn CorpusSource>],
+    merge: Merge,
+) -> (HashMap<String, ElementoRecord>, HashMap<String, LemaRecord>) {
+    let mut loaded = sources
+        .iter()
+        .map(|source| (source.elementos(), source.lemas()));
+    let Some((mut elementos, mut lemas)) = loaded.next() else {
+        return (HashMap::new(), HashMap::new());
+    };
+
+    for (other_elementos, other_lemas) in loaded {
+        merge_frequencies(&mut elementos, other_elementos, merge, |r| {
+            (&mut r.3, &mut r.4, &mut r.5)
+        });
+        merge_frequencies(&mut lemas, other_lemas, merge, |r| {
+            (&mut r.2, &mut r.3, &mut r.4)
+        });
+    }
+
+    if sources.len() > 1 {
+        println!(
+            "Merged {} sources into {} elementos and {} lemas",
+            sources.len(),
+            elementos.len(),
+            lemas.len()
+        );
+    }
+
+    (elementos, lemas)
+}
+
+fn merge_frequencies<R>(
+    records: &mut HashMap<String, R>,
+    mut other: HashMap<String, R>,
+    merge: Merge,
+    frequencies: impl Fn(&mut R) -> (&mut usize, &mut f64, &mut f64),
+) {
+    // A word missing from a source has frequency zero there
+    if let Merge::Min = merge {
+        records.retain(|word, _| other.contains_key(word));
+    }
+
+    for (word, mut record) in other.drain() {
+        let Some(existing) = records.get_mut(&word) else {
+            if let Merge::Max = merge {
+                records.insert(word, record);
+            }
+            continue;
+        };
+        let (freq, norm_sin, norm) = frequencies(existing);
+        let (other_freq, other_norm_sin, other_norm) = frequencies(&mut record);
+        match merge {
+            Merge::Max => {
+                *freq = (*freq).max(*other_freq);
+                *norm_sin = norm_sin.max(*other_norm_sin);
+                *norm = norm.max(*other_norm);
+            }
+            Merge::Min => {
+                *freq = (*freq).min(*other_freq);
+                *norm_sin = norm_sin.min(*other_norm_sin);
+                *norm = norm.min(*other_norm);
+            }
+        }
+    }
+}
+
+/// Collect records into an elementos map. The frequencies of a form listed
+/// several times, e.g. under several lemas, are added up, and the lema and
+/// category of its most frequent record are kept.
+fn collect_elementos(
+    records: impl Iterator<Item = ElementoRecord>,
+) -> HashMap<String, ElementoRecord> {
+    // Map elemento -> (record, frequency of its most frequent record)
+    let mut elementos: HashMap<String, (ElementoRecord, usize)> = HashMap::new();
+    records.for_each(|record| match elementos.get_mut(&record.0) {
+        Some((r, most_frequent)) => {
+            if record.3 > *most_frequent {
+                *most_frequent = record.3;
+                r.1 = record.1;
+                r.2 = record.2;
+            }
+            r.3 += record.3;
+            r.4 += record.4;
+            r.5 += record.5;
+        }
+        None => {
+            let freq = record.3;
+            elementos.insert(record.0.clone(), (record, freq));
+        }
+    });
+    elementos
+        .into_iter()
+        .map(|(elemento, (record, _))| (elemento, record))
+        .collect()
+}
+
+/// Add up the frequencies of all the elementos of each lema
+pub fn lemas_from_elementos(
+    elementos: &HashMap<String, ElementoRecord>,
+) -> HashMap<String, LemaRecord> {
+    let mut lemas: HashMap<String, LemaRecord> = HashMap::new();
+    elementos
+        .values()
+        .for_each(|(_, lema, categoria, freq, norm_sin, norm)| {
+            lemas
+                .entry(lema.clone())
+                .and_modify(|r| {
+                    r.2 += freq;
+                    r.3 += norm_sin;
+                    r.4 += norm;
+                })
+                .or_insert((lema.clone(), *categoria, *freq, *norm_sin, *norm));
+        });
+    lemas
+}
+
+fn parse_spanish_usize(s: &str) -> Option<usize> {
+    s.trim().replace(['.', ' '], "").parse().ok()
+}
+
+fn parse_spanish_f64(s: &str) -> Option<f64> {
+    let s = s.trim().replace(' ', "");
+    if s.contains(',') {
+        s.replace('.', "").replace(',', ".").parse().ok()
+    } else {
+        s.parse().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn most_frequent_lema_wins() {
+        let record = |lema: &str, categoria, freq| {
+            (
+                "vino".to_string(),
+                lema.to_string(),
+                categoria,
+                freq,
+                1.0,
+                1.0,
+            )
+        };
+        let elementos = collect_elementos(
+            [
+                record("venir", Categoria::V, 10),
+                record("vino", Categoria::N, 30),
+                record("venir", Categoria::V, 20),
+            ]
+            .into_iter(),
+        );
+
+        let (_, lema, categoria, freq, norm_sin, norm) = &elementos["vino"];
+        assert_eq!(lema, "vino");
+        assert_eq!(*categoria, Categoria::N);
+        assert_eq!(*freq, 60);
+        assert_eq!((*norm_sin, *norm), (3.0, 3.0));
+    }
+}
@@ -0,0 +1,32 @@
+/// Normalized frequency (per million) at and above which a word is not rare
+const COMMON_FREQ: f64 = 1000.0;
+/// Normalized frequency (per million) at and below which a word is very rare
+const RARE_FREQ: f64 = 0.1;
+
+/// Estimate how hard a word is to find, from 0 (easy) to 1 (hard). Rare words
+/// of rare lemmas are hard, as are long words and forms far from their lemma,
+/// e.g. "cantábamos" is harder than "cantar".
+pub fn difficulty(word: &str, lemma: &str, freq: f64, lemma_freq: f64) -> f32 {
+    let form_rarity = rarity(freq);
+    let lemma_rarity = rarity(lemma_freq);
+    let length = ((word.chars().count() as f64 - 4.0) / 8.0).clamp(0.0, 1.0);
+    let inflection = (inflection_depth(word, lemma) as f64 / 6.0).clamp(0.0, 1.0);
+
+    (0.45 * form_rarity + 0.2 * lemma_rarity + 0.2 * length + 0.15 * inflection) as f32
+}
+
+/// How rare a normalized frequency is on a log scale, from 0 to 1
+fn rarity(freq: f64) -> f64 {
+    let (common, rare) = (COMMON_FREQ.log10(), RARE_FREQ.log10());
+    ((common - freq.max(RARE_FREQ).log10()) / (common - rare)).clamp(0.0, 1.0)
+}
+
+/// Number of characters of the form after the prefix it shares with its lemma
+fn inflection_depth(word: &str, lemma: &str) -> usize {
+    let prefix = word
+        .chars()
+        .zip(lemma.chars())
+        .take_while(|(a, b)| a == b)
+        .count();
+    word.chars().count() - prefix
+}
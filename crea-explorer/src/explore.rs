@@ -2,6 +2,7 @@ use std::collections::HashSet;
 
 use crate::{filter, parser};
 use inquire::{InquireError, Text};
+use words::Language;
 
 pub fn explore() {
     let elementos = parser::parse_elementos();
@@ -29,6 +30,7 @@ pub fn explore() {
             found_something = true;
             println!("Elemento: {:?}", elemento);
             if filter(
+                Language::Es,
                 &elemento.0,
                 Some(&elemento.1),
                 Some(elemento.2),
@@ -42,12 +44,14 @@ pub fn explore() {
             if let Some(elementos_with_this_lema) = elementos_by_lema.get(&elemento.1) {
                 let validateds: HashSet<&String> = elementos_with_this_lema
                     .iter()
-                    .filter(|word| crate::filter(word, None, None, usize::MAX, true).0)
+                    .filter(|word| {
+                        crate::filter(Language::Es, word, None, None, usize::MAX, true).0
+                    })
                     .collect();
                 if elemento.0 == elemento.1 {
                     print!("Elementos by lema: ({}) ", validateds.len());
                     validateds.iter().for_each(|word| print!("{}, ", word));
-                    println!("");
+                    println!();
                 } else if validateds.contains(&elemento.0) {
                     if let Some(origin) = elementos.get(&elemento.1) {
                         println!("Elementos by lema: {} from {:?}", elemento.0, origin);
@@ -60,7 +64,7 @@ pub fn explore() {
         if let Some(forma) = formas.get(&word) {
             found_something = true;
             println!("Forma: {:?}", forma);
-            if filter(&forma.0, None, None, forma.1, false).0 {
+            if filter(Language::Es, &forma.0, None, None, forma.1, false).0 {
                 println!("\tValid");
             }
         }
@@ -68,7 +72,7 @@ pub fn explore() {
         if let Some(lema) = lemas.get(&word) {
             found_something = true;
             println!("Lema: {:?}", lema);
-            if filter(&lema.0, None, Some(lema.1), lema.2, false).0 {
+            if filter(Language::Es, &lema.0, None, Some(lema.1), lema.2, false).0 {
                 println!("\tValid");
             }
         }
@@ -1,3 +1,5 @@
+use words::Language;
+
 use crate::{
     filter, generate,
     parser::{self, ElementoRecord},
@@ -16,7 +18,7 @@ pub fn frequency(min_freq: usize, words: usize) {
         .into_iter()
         // Only care about words that filted due to their frequency
         .filter(|(word, (_, lema, cat, _, _, _))| {
-            filter(word, Some(lema), Some(*cat), usize::MAX, true).0
+            filter(Language::Es, word, Some(lema), Some(*cat), usize::MAX, true).0
         })
         // Second pass to ignore words derived from valid lemas
         .filter(|(word, (_, lema, _, _, _, _))| {
@@ -1,16 +1,189 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use words::{Exclusion, Language};
 
-use crate::{filter, parser};
+use crate::{
+    corpus::{self, Merge, SourceSpec},
+    difficulty, filter, parser,
+    parser::{Categoria, ElementoRecord, LemaRecord},
+    region::{RegionalFrequencies, Variant},
+    MIN_FREQ,
+};
 
-pub fn generate() {
-    let (words, pangrams) = generate_words_and_pangrams();
-    write_palabras_rs(words, pangrams);
+#[derive(clap::Args, Debug)]
+pub struct Options {
+    /// Language of the word list: es, pt, ca, or en. Only es is played: other
+    /// languages are written to palabras_<code>.rs, which the words crate
+    /// does not use yet
+    #[arg(long, default_value_t)]
+    language: Language,
+    /// Corpus to build the word list from: crea, corpes:<file>, or
+    /// tsv:<file>. Repeat to merge several sources
+    #[arg(long = "source", default_value = "crea")]
+    sources: Vec<SourceSpec>,
+    /// How to merge the frequencies of several sources
+    #[arg(long, value_enum, default_value_t)]
+    merge: Merge,
+    /// Regional variant: general, a region (e.g. spain, mexico,
+    /// rioplatense), or pan-hispanic[:<regions>] for words common in at least
+    /// that many regions
+    #[arg(long, default_value = "general")]
+    variant: Variant,
+    /// Per-country frequencies as elemento<TAB>country<TAB>freq, required for
+    /// regional variants
+    #[arg(long)]
+    countries: Option<PathBuf>,
+}
+
+pub fn generate(options: Options) {
+    let Options {
+        language,
+        sources,
+        merge,
+        variant,
+        countries,
+    } = options;
+    if language != Language::Es && sources.iter().any(|spec| matches!(spec, SourceSpec::Crea)) {
+        println!(
+            "The CREA is a Spanish corpus, use another source for {}",
+            language
+        );
+        return;
+    }
+    let regional = match (variant, countries) {
+        (Variant::General, _) => None,
+        (_, Some(path)) => Some(RegionalFrequencies::parse(&path)),
+        (_, None) => {
+            println!("The {} variant requires --countries", variant);
+            return;
+        }
+    };
+
+    let sources: Vec<_> = sources.iter().map(|spec| spec.source()).collect();
+    let (mut elements, mut lemmas) = corpus::merge_sources(&sources, merge);
+    if let Some(regional) = regional {
+        regional.apply(variant, &mut elements, &mut lemmas);
+    }
+    let (words, pangrams) = generate_words_and_pangrams_from(language, &elements, &lemmas);
+    let metadata = metadata(&words, &elements, &lemmas);
+    let excluded = excluded(language, &words, &elements, &lemmas);
+    write_palabras_rs(language, variant, words, pangrams, metadata, excluded);
+}
+
+/// Words rarer than this are not worth telling players about
+const MIN_EXCLUDED_FREQ: usize = 5;
+
+/// Get the (normalized form, form, reason) of the elements left out of the
+/// word list that could otherwise be played, so that players can be told why
+/// they were rejected
+fn excluded(
+    language: Language,
+    words: &[(String, String)],
+    elements: &HashMap<String, ElementoRecord>,
+    lemmas: &HashMap<String, LemaRecord>,
+) -> Vec<(String, String, Exclusion)> {
+    let forms: HashSet<&String> = words.iter().map(|(form, _)| form).collect();
+    let mut excluded: Vec<(String, String, Exclusion)> = elements
+        .values()
+        .filter(|(element, ..)| !forms.contains(element))
+        .filter_map(|(element, lemma, category, freq, _, _)| {
+            let lemma_freq = lemmas.get(lemma).map_or(0, |(_, _, freq, _, _)| *freq);
+            let freq = (*freq).max(lemma_freq);
+            let form = element.to_lowercase();
+            let normalized = language.normalize(&form);
+            // Only words that could be played in some puzzle
+            let letters: HashSet<char> = normalized.chars().collect();
+            if normalized.chars().count() < 4
+                || letters.len() > 7
+                || !language.is_written_in(&form)
+                || freq < MIN_EXCLUDED_FREQ
+            {
+                return None;
+            }
+
+            let exclusion = if &form != element || lemma.to_lowercase() != *lemma {
+                Exclusion::ProperNoun
+            } else if *category == Categoria::F {
+                Exclusion::Foreign
+            } else if freq < MIN_FREQ {
+                Exclusion::Rare
+            } else {
+                return None;
+            };
+            Some((normalized, form, exclusion))
+        })
+        .collect();
+    excluded.sort_by(|(a, a_form, _), (b, b_form, _)| (a, a_form).cmp(&(b, b_form)));
+    excluded.dedup_by(|(a, a_form, _), (b, b_form, _)| a == b && a_form == b_form);
+    println!("Found {} excluded words", excluded.len());
+    excluded
+}
+
+/// Get the category, normalized frequency, lemma normalized frequency, and
+/// difficulty of each word
+fn metadata(
+    words: &[(String, String)],
+    elements: &HashMap<String, ElementoRecord>,
+    lemmas: &HashMap<String, LemaRecord>,
+) -> Vec<(Categoria, f32, f32, f32)> {
+    words
+        .iter()
+        .map(|(word, lemma)| {
+            let (category, freq) = elements
+                .get(word)
+                .map_or((Categoria::U, 0.0), |(_, _, category, _, _, freq)| {
+                    (*category, *freq)
+                });
+            let lemma_freq = lemmas
+                .get(lemma)
+                .map_or(freq, |(_, _, _, _, lemma_freq)| *lemma_freq);
+            let difficulty = difficulty::difficulty(word, lemma, freq, lemma_freq);
+            (category, freq as f32, lemma_freq as f32, difficulty)
+        })
+        .collect()
+}
+
+/// Compare the word lists generated from two sources
+pub fn compare(language: Language, a: SourceSpec, b: SourceSpec, words: usize) {
+    let (a, b) = (a.source(), b.source());
+    let (a_words, _) = generate_words_and_pangrams_from(language, &a.elementos(), &a.lemas());
+    let (b_words, _) = generate_words_and_pangrams_from(language, &b.elementos(), &b.lemas());
+    let a_forms: HashSet<&String> = a_words.iter().map(|(form, _)| form).collect();
+    let b_forms: HashSet<&String> = b_words.iter().map(|(form, _)| form).collect();
+
+    println!(
+        "{} words in both, {} only in {}, {} only in {}",
+        a_forms.intersection(&b_forms).count(),
+        a_forms.difference(&b_forms).count(),
+        a.name(),
+        b_forms.difference(&a_forms).count(),
+        b.name()
+    );
+    for (name, only) in [
+        (a.name(), a_forms.difference(&b_forms)),
+        (b.name(), b_forms.difference(&a_forms)),
+    ] {
+        let mut only: Vec<&&String> = only.collect();
+        only.sort();
+        print!("Only in {}: ", name);
+        only.iter()
+            .take(words)
+            .for_each(|word| print!("{}, ", word));
+        println!();
+    }
 }
 
 pub fn generate_words_and_pangrams() -> (Vec<(String, String)>, Vec<String>) {
     let elements = parser::parse_elementos();
     let lemmas = parser::parse_lemas();
+    generate_words_and_pangrams_from(Language::Es, &elements, &lemmas)
+}
 
+pub fn generate_words_and_pangrams_from(
+    language: Language,
+    elements: &HashMap<String, ElementoRecord>,
+    lemmas: &HashMap<String, LemaRecord>,
+) -> (Vec<(String, String)>, Vec<String>) {
     println!("Filtering words and common pangrams...");
     // List of *normalized* common pangrams for puzzle generation
     let mut pangrams: HashSet<String> = HashSet::new();
@@ -23,13 +196,13 @@ pub fn generate_words_and_pangrams() -> (Vec<(String, String)>, Vec<String>) {
                 println!("{}, {}, {:?}, {}", element, lemma, category, freq);
             }
             if lemma == &String::from("??")
-                || unidecode::unidecode(lemma) != unidecode::unidecode(lemma).to_ascii_lowercase()
+                || language.normalize(lemma) != language.normalize(lemma).to_lowercase()
             {
                 return;
             }
             // See if this is a valid word and common pangram
             let (valid, common_pangram) =
-                filter(element, Some(lemma), Some(*category), *freq, true);
+                filter(language, element, Some(lemma), Some(*category), *freq, true);
             if !valid {
                 // If not valid, do a last check to see if its valid using its lemma's frequency
                 let Some((_, _, lemma_freq, _, _)) = lemmas.get(lemma) else {
@@ -38,14 +211,20 @@ pub fn generate_words_and_pangrams() -> (Vec<(String, String)>, Vec<String>) {
                 if element == &String::from("abarcas") {
                     println!("by lemma freq: {}", lemma_freq);
                 }
-                let (valid_by_lemma, _) =
-                    filter(element, Some(lemma), Some(*category), *lemma_freq, true);
+                let (valid_by_lemma, _) = filter(
+                    language,
+                    element,
+                    Some(lemma),
+                    Some(*category),
+                    *lemma_freq,
+                    true,
+                );
                 if !valid_by_lemma {
                     return;
                 }
             }
             if common_pangram {
-                pangrams.insert(unidecode::unidecode(element));
+                pangrams.insert(language.normalize(element));
             }
             words.insert((element.to_string(), lemma.to_string()));
         });
@@ -65,13 +244,31 @@ pub fn generate_words_and_pangrams() -> (Vec<(String, String)>, Vec<String>) {
     (words, pangrams)
 }
 
-fn write_palabras_rs(words: Vec<(String, String)>, pangrams: Vec<String>) {
-    println!("Writing palabras.rs...");
+fn write_palabras_rs(
+    language: Language,
+    variant: Variant,
+    words: Vec<(String, String)>,
+    pangrams: Vec<String>,
+    metadata: Vec<(Categoria, f32, f32, f32)>,
+    excluded: Vec<(String, String, Exclusion)>,
+) {
+    // Spanish is the original word list, other languages get their own file
+    let path = match language {
+        Language::Es => "palabras.rs".to_string(),
+        _ => format!("palabras_{}.rs", language),
+    };
+    println!("Writing {}...", path);
+    let mut palabras_rs = String::from("use crate::{Categoria, Exclusion};\n\n");
+    // Variant
+    palabras_rs.push_str(&format!(
+        "pub const VARIANT: &str = {:?};\n\n",
+        variant.to_string()
+    ));
     // Words
-    let mut palabras_rs = format!(
+    palabras_rs.push_str(&format!(
         "pub const PALABRAS: &[(&str, &str); {}] = &[\n",
         words.len()
-    );
+    ));
     words
         .iter()
         .for_each(|word| palabras_rs.push_str(&format!("    {:?},\n", word)));
@@ -84,6 +281,32 @@ fn write_palabras_rs(words: Vec<(String, String)>, pangrams: Vec<String>) {
     pangrams
         .iter()
         .for_each(|word| palabras_rs.push_str(&format!("    {:?},\n", word)));
+    palabras_rs.push_str("];\n\n");
+    // Metadata, in the same order as the words
+    palabras_rs.push_str(&format!(
+        "pub const METADATA: &[(Categoria, f32, f32, f32); {}] = &[\n",
+        metadata.len()
+    ));
+    metadata
+        .iter()
+        .for_each(|(category, freq, lemma_freq, difficulty)| {
+            palabras_rs.push_str(&format!(
+                "    (Categoria::{:?}, {:?}, {:?}, {:?}),\n",
+                category, freq, lemma_freq, difficulty
+            ))
+        });
+    palabras_rs.push_str("];\n\n");
+    // Excluded words
+    palabras_rs.push_str(&format!(
+        "pub const EXCLUIDAS: &[(&str, &str, Exclusion); {}] = &[\n",
+        excluded.len()
+    ));
+    excluded.iter().for_each(|(normalized, form, exclusion)| {
+        palabras_rs.push_str(&format!(
+            "    ({:?}, {:?}, Exclusion::{:?}),\n",
+            normalized, form, exclusion
+        ))
+    });
     palabras_rs.push_str("];\n");
-    std::fs::write("palabras.rs", palabras_rs).expect("Unable to write palabras.rs");
+    std::fs::write(&path, palabras_rs).unwrap_or_else(|_| panic!("Unable to write {}", path));
 }
@@ -1,12 +1,17 @@
 use clap::{Parser, Subcommand};
+use corpus::SourceSpec;
 use parser::Categoria;
 use std::collections::HashSet;
-use unidecode::unidecode;
+use words::Language;
 
+mod corpus;
+mod difficulty;
 mod explore;
 mod frequency;
 mod generate;
 mod parser;
+mod region;
+mod rejections;
 
 /// Program to explore the RAE's CREA and generate the wordlists for the game
 #[derive(Parser, Debug)]
@@ -20,7 +25,22 @@ enum Command {
     /// Explore the CREA files
     Explore,
     /// Generate palabras.rs
-    Generate,
+    Generate(generate::Options),
+    /// Compare the word lists generated from two corpora
+    Compare {
+        /// Language of the word lists: es, pt, ca, or en
+        #[arg(long, default_value_t)]
+        language: Language,
+        /// First corpus: crea, corpes:<file>, or tsv:<file>
+        a: SourceSpec,
+        /// Second corpus: crea, corpes:<file>, or tsv:<file>
+        b: SourceSpec,
+        /// Number of differing words to display from each
+        #[arg(default_value_t = 10)]
+        words: usize,
+    },
+    /// Propose words for the word list from logs of rejected submissions
+    ReviewRejections(rejections::Options),
     /// View words omitted by a minimum frequency
     Frequency {
         /// Minimum frequency
@@ -36,12 +56,23 @@ fn main() {
 
     match args.command {
         Command::Explore => explore::explore(),
-        Command::Generate => generate::generate(),
+        Command::Generate(options) => generate::generate(options),
+        Command::Compare {
+            language,
+            a,
+            b,
+            words,
+        } => generate::compare(language, a, b, words),
+        Command::ReviewRejections(options) => rejections::review_rejections(options),
         Command::Frequency { freq, words } => frequency::frequency(freq, words),
     }
 }
 
+/// Words less frequent than this are too rare for the word list
+pub const MIN_FREQ: usize = 50;
+
 pub fn filter(
+    language: Language,
     word: &String,
     lema: Option<&String>,
     category: Option<Categoria>,
@@ -54,7 +85,7 @@ pub fn filter(
     let mut common_pangram = false;
 
     // Words must be decently common
-    if freq < 50 {
+    if freq < MIN_FREQ {
         if short_circuit {
             return (false, common_pangram);
         };
@@ -100,12 +131,13 @@ pub fn filter(
         valid = false;
     }
 
-    // Words must be all lowercase letters (no spaces or other funny
-    // business)
-    let stripped = unidecode(word);
-    if !stripped
-        .chars()
-        .all(|c| c.is_ascii_alphabetic() && c.is_lowercase())
+    // Words must be all lowercase letters of the language's alphabet (no
+    // spaces or other funny business)
+    let stripped = language.normalize(word);
+    if !language.is_written_in(word)
+        || !stripped
+            .chars()
+            .all(|c| c.is_ascii_alphabetic() && c.is_lowercase())
     {
         if short_circuit {
             return (false, common_pangram);
@@ -133,6 +165,6 @@ pub fn filter(
 }
 
 fn count_unique_chars(word: &str) -> usize {
-    let set: HashSet<char> = HashSet::from_iter(unidecode(word).chars());
+    let set: HashSet<char> = HashSet::from_iter(word.chars());
     set.len()
 }
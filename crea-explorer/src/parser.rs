@@ -1,49 +1,6 @@
-use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-#[derive(Clone, Copy, Debug, Eq, Deserialize, PartialEq, Serialize)]
-pub enum Categoria {
-    /// Adjetivo
-    A,
-    /// Adverbio
-    R,
-    /// Afijo
-    J,
-    /// Artículo
-    T,
-    /// Conjunción
-    C,
-    /// Contracción
-    E,
-    /// Cuantificador
-    Q,
-    /// Demostrativo
-    D,
-    /// Desconocido
-    U,
-    /// Extranjerismo
-    F,
-    /// Interjección
-    I,
-    /// Interrogativo
-    W,
-    /// Numeral
-    M,
-    /// Posesivo
-    X,
-    /// Preposición
-    P,
-    /// Pronombre personal
-    L,
-    /// Puntación
-    Y,
-    /// Relativo
-    H,
-    /// Sustantivo
-    N,
-    /// Verbo
-    V,
-}
+pub use words::Categoria;
 
 /// Elemento, Lema, Categoría, Frecuencia con signos ort., Frec norm. sin signos ort., Frec. norm
 pub type ElementoRecord = (String, String, Categoria, usize, f64, f64);
@@ -0,0 +1,228 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::str::FromStr;
+
+use clap::ValueEnum;
+use unidecode::unidecode;
+
+use crate::parser::{ElementoRecord, LemaRecord};
+
+/// Dialectal regions of Spanish, grouping the CREA's countries
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, ValueEnum)]
+pub enum Region {
+    Spain,
+    Mexico,
+    CentralAmerica,
+    Caribbean,
+    Andes,
+    Rioplatense,
+    Chile,
+    UnitedStates,
+}
+
+impl Region {
+    /// Get the region of a country, by its CREA name (`MÉXICO`) or ISO 3166
+    /// code (`MX`)
+    pub fn from_country(country: &str) -> Option<Region> {
+        let country = unidecode(country.trim()).to_uppercase();
+        let region = match country.as_str() {
+            "ESPANA" | "ES" => Region::Spain,
+            "MEXICO" | "MX" => Region::Mexico,
+            "GUATEMALA" | "GT" | "HONDURAS" | "HN" | "EL SALVADOR" | "SV" | "NICARAGUA" | "NI"
+            | "COSTA RICA" | "CR" | "PANAMA" | "PA" => Region::CentralAmerica,
+            "CUBA"
+            | "CU"
+            | "REPUBLICA DOMINICANA"
+            | "DO"
+            | "PUERTO RICO"
+            | "PR"
+            | "VENEZUELA"
+            | "VE" => Region::Caribbean,
+            "COLOMBIA" | "CO" | "ECUADOR" | "EC" | "PERU" | "PE" | "BOLIVIA" | "BO" => {
+                Region::Andes
+            }
+            "ARGENTINA" | "AR" | "URUGUAY" | "UY" | "PARAGUAY" | "PY" => Region::Rioplatense,
+            "CHILE" | "CL" => Region::Chile,
+            "ESTADOS UNIDOS" | "EE. UU." | "EE.UU." | "US" => Region::UnitedStates,
+            _ => return None,
+        };
+        Some(region)
+    }
+}
+
+/// Which regions' usage a word list is built from
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Variant {
+    /// Frequency across the whole corpus
+    #[default]
+    General,
+    /// Frequency within a single region
+    Region(Region),
+    /// Common in at least this many regions
+    PanHispanic { min_regions: usize },
+}
+
+impl FromStr for Variant {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            None if s == "general" => Ok(Variant::General),
+            None if s == "pan-hispanic" => Ok(Variant::PanHispanic { min_regions: 3 }),
+            Some(("pan-hispanic", min_regions)) => min_regions
+                .parse()
+                .map(|min_regions| Variant::PanHispanic { min_regions })
+                .map_err(|_| format!("invalid number of regions {:?}", min_regions)),
+            _ => Region::from_str(s, true).map(Variant::Region).map_err(|_| {
+                format!(
+                    "unknown variant {:?}, expected general, pan-hispanic[:<regions>], or a region",
+                    s
+                )
+            }),
+        }
+    }
+}
+
+impl std::fmt::Display for Variant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Variant::General => write!(f, "general"),
+            Variant::Region(region) => match region.to_possible_value() {
+                Some(value) => write!(f, "{}", value.get_name()),
+                None => write!(f, "{:?}", region),
+            },
+            Variant::PanHispanic { min_regions } => write!(f, "pan-hispanic:{}", min_regions),
+        }
+    }
+}
+
+/// Per-region frequencies of elementos
+pub struct RegionalFrequencies {
+    frequencies: HashMap<String, HashMap<Region, usize>>,
+    totals: HashMap<Region, usize>,
+}
+
+impl RegionalFrequencies {
+    /// Parse a headerless `elemento<TAB>country<TAB>freq` file. Countries
+    /// outside of the [Region]s are ignored.
+    pub fn parse(path: &Path) -> RegionalFrequencies {
+        println!("Parsing country frequencies {}...", path.display());
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(b'\t')
+            .has_headers(false)
+            .flexible(true)
+            .from_path(path)
+            .unwrap_or_else(|_| panic!("Unable to read {}", path.display()));
+        let mut frequencies: HashMap<String, HashMap<Region, usize>> = HashMap::new();
+        let mut totals: HashMap<Region, usize> = HashMap::new();
+        reader
+            .records()
+            .filter_map(|record| record.ok())
+            .filter_map(|record| {
+                let elemento = record.get(0)?.trim().to_string();
+                let region = Region::from_country(record.get(1)?)?;
+                let freq: usize = record.get(2)?.trim().parse().ok()?;
+                Some((elemento, region, freq))
+            })
+            .for_each(|(elemento, region, freq)| {
+                *frequencies
+                    .entry(elemento)
+                    .or_default()
+                    .entry(region)
+                    .or_default() += freq;
+                *totals.entry(region).or_default() += freq;
+            });
+        println!(
+            "Found frequencies of {} elementos in {} regions",
+            frequencies.len(),
+            totals.len()
+        );
+        RegionalFrequencies {
+            frequencies,
+            totals,
+        }
+    }
+
+    /// The frequency of an elemento in the variant, scaled to the size of the
+    /// whole corpus so that it is comparable to the general frequency. `None`
+    /// if there is no regional data for the elemento.
+    pub fn frequency(&self, elemento: &str, variant: Variant) -> Option<usize> {
+        let by_region = self.frequencies.get(elemento)?;
+        let total: usize = self.totals.values().sum();
+        let scaled = |region: Region| -> usize {
+            let region_total = self.totals.get(&region).copied().unwrap_or(0);
+            if region_total == 0 {
+                return 0;
+            }
+            let freq = by_region.get(&region).copied().unwrap_or(0);
+            (freq as f64 * total as f64 / region_total as f64).round() as usize
+        };
+
+        match variant {
+            Variant::General => Some(by_region.values().sum()),
+            Variant::Region(region) => Some(scaled(region)),
+            Variant::PanHispanic { min_regions } => {
+                // The word is as common as it is in its min_regions-th most
+                // common region
+                let mut freqs: Vec<usize> = self.totals.keys().map(|&r| scaled(r)).collect();
+                freqs.sort_by_key(|&freq| std::cmp::Reverse(freq));
+                Some(
+                    freqs
+                        .get(min_regions.saturating_sub(1))
+                        .copied()
+                        .unwrap_or(0),
+                )
+            }
+        }
+    }
+
+    /// Replace the frequencies of elementos and lemas by their frequencies in
+    /// the variant, where regional data is available. The normalized
+    /// frequencies are recomputed from the new frequencies, so that the
+    /// commonness and difficulty of the metadata are also the variant's.
+    pub fn apply(
+        &self,
+        variant: Variant,
+        elementos: &mut HashMap<String, ElementoRecord>,
+        lemas: &mut HashMap<String, LemaRecord>,
+    ) {
+        if variant == Variant::General {
+            return;
+        }
+
+        println!("Applying the {} variant...", variant);
+        let (elemento_norm, elemento_norm_all) =
+            per_occurrence(elementos.values().map(|r| (r.3, r.4, r.5)));
+        let (lema_norm, lema_norm_all) = per_occurrence(lemas.values().map(|r| (r.2, r.3, r.4)));
+
+        let mut lema_freqs: HashMap<String, usize> = HashMap::new();
+        elementos.iter_mut().for_each(|(elemento, record)| {
+            if let Some(freq) = self.frequency(elemento, variant) {
+                record.3 = freq;
+                record.4 = freq as f64 * elemento_norm;
+                record.5 = freq as f64 * elemento_norm_all;
+                *lema_freqs.entry(record.1.clone()).or_default() += freq;
+            }
+        });
+        lemas.iter_mut().for_each(|(lema, record)| {
+            if let Some(&freq) = lema_freqs.get(lema) {
+                record.2 = freq;
+                record.3 = freq as f64 * lema_norm;
+                record.4 = freq as f64 * lema_norm_all;
+            }
+        });
+    }
+}
+
+/// The normalized frequencies (without and with punctuation) per occurrence
+/// of records of (frequency, normalized frequencies), which are the same for
+/// every record of a corpus
+fn per_occurrence(records: impl Iterator<Item = (usize, f64, f64)>) -> (f64, f64) {
+    let (freq, norm, norm_all) = records.fold((0, 0.0, 0.0), |(freq, norm, norm_all), record| {
+        (freq + record.0, norm + record.1, norm_all + record.2)
+    });
+    match freq {
+        0 => (0.0, 0.0),
+        _ => (norm / freq as f64, norm_all / freq as f64),
+    }
+}
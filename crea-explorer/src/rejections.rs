@@ -0,0 +1,208 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::PathBuf;
+
+use serde::Deserialize;
+use words::Language;
+
+use crate::{
+    corpus::{self, Merge, SourceSpec},
+    parser::ElementoRecord,
+};
+
+#[derive(clap::Args, Debug)]
+pub struct Options {
+    /// JSON lines logs of rejected submissions, as written by the game
+    #[arg(required = true)]
+    logs: Vec<PathBuf>,
+    /// Language of the submissions to review. Only es has a word list
+    #[arg(long, default_value_t)]
+    language: Language,
+    /// Corpus to look the words up in: crea, corpes:<file>, or tsv:<file>.
+    /// Repeat to merge several sources
+    #[arg(long = "source", default_value = "crea")]
+    sources: Vec<SourceSpec>,
+    /// How to merge the frequencies of several sources
+    #[arg(long, value_enum, default_value_t)]
+    merge: Merge,
+    /// Minimum number of times a word must have been submitted
+    #[arg(long, default_value_t = 3)]
+    min_submissions: usize,
+    /// Minimum frequency of a word in the corpus
+    #[arg(long, default_value_t = 5)]
+    min_freq: usize,
+    /// Write the proposed words to this file, one per line
+    #[arg(long)]
+    output: Option<PathBuf>,
+    /// Number of proposals to display
+    #[arg(long, default_value_t = 50)]
+    words: usize,
+}
+
+/// The fields of a logged rejection needed for the review
+#[derive(Deserialize)]
+struct Rejection {
+    word: String,
+    #[serde(default)]
+    language: Language,
+    #[serde(default)]
+    near_miss: Option<NearMiss>,
+}
+
+#[derive(Deserialize)]
+struct NearMiss {
+    kind: String,
+    #[serde(default)]
+    in_word_list: bool,
+}
+
+/// Add up the rejections of a language in a log, into a map word ->
+/// (submissions, near miss kinds). Lines that are not rejections are skipped.
+fn tally(
+    log: &str,
+    language: Language,
+    rejections: &mut HashMap<String, (usize, BTreeSet<String>)>,
+) {
+    log.lines()
+        .filter_map(|line| serde_json::from_str::<Rejection>(line).ok())
+        .filter(|rejection| rejection.language == language)
+        // Words already in the list were only rejected by the puzzle
+        .filter(|rejection| !rejection.near_miss.as_ref().is_some_and(|n| n.in_word_list))
+        .for_each(|rejection| {
+            let (submissions, kinds) = rejections.entry(rejection.word).or_default();
+            *submissions += 1;
+            if let Some(near_miss) = rejection.near_miss {
+                kinds.insert(near_miss.kind);
+            }
+        });
+}
+
+/// Aggregate logs of rejected submissions and propose the real words that
+/// players keep trying for the word list
+pub fn review_rejections(options: Options) {
+    let Options {
+        logs,
+        language,
+        sources,
+        merge,
+        min_submissions,
+        min_freq,
+        output,
+        words,
+    } = options;
+    let Some(dictionary) = words::dictionary(language) else {
+        println!("There is no word list for {}", language);
+        return;
+    };
+
+    let mut rejections = HashMap::new();
+    logs.iter().for_each(|path| {
+        println!("Reading rejections {}...", path.display());
+        let log = std::fs::read_to_string(path)
+            .unwrap_or_else(|_| panic!("Unable to read {}", path.display()));
+        tally(&log, language, &mut rejections);
+    });
+    println!("Found {} rejected words", rejections.len());
+
+    let sources: Vec<_> = sources.iter().map(|spec| spec.source()).collect();
+    let (elements, _) = corpus::merge_sources(&sources, merge);
+    // Words may have been typed without their accents
+    let mut by_normalized: HashMap<String, &ElementoRecord> = HashMap::new();
+    elements.values().for_each(|record| {
+        let entry = by_normalized
+            .entry(language.normalize(&record.0))
+            .or_insert(record);
+        if record.3 > entry.3 {
+            *entry = record;
+        }
+    });
+    let listed: HashSet<&str> = dictionary.words.iter().map(|(form, _)| *form).collect();
+
+    // Words typed with and without accents are the same proposal
+    let mut proposals: HashMap<&str, (&ElementoRecord, usize, BTreeSet<String>)> = HashMap::new();
+    rejections
+        .iter()
+        .filter_map(|(word, (submissions, kinds))| {
+            let record = elements
+                .get(word)
+                .or_else(|| by_normalized.get(&language.normalize(word)).copied())?;
+            Some((record, submissions, kinds))
+        })
+        .filter(|(record, _, _)| record.3 >= min_freq && !listed.contains(record.0.as_str()))
+        .for_each(|(record, submissions, kinds)| {
+            let proposal = proposals
+                .entry(&record.0)
+                .or_insert((record, 0, BTreeSet::new()));
+            proposal.1 += submissions;
+            proposal.2.extend(kinds.iter().cloned());
+        });
+    let mut proposals: Vec<(&ElementoRecord, usize, BTreeSet<String>)> = proposals
+        .into_values()
+        .filter(|(_, submissions, _)| *submissions >= min_submissions)
+        .collect();
+    proposals.sort_by(|(a, a_submissions, _), (b, b_submissions, _)| {
+        b_submissions.cmp(a_submissions).then(a.0.cmp(&b.0))
+    });
+
+    println!(
+        "Proposing {} words submitted at least {} times with frequency at least {}",
+        proposals.len(),
+        min_submissions,
+        min_freq
+    );
+    let width = proposals
+        .iter()
+        .map(|(record, ..)| record.0.chars().count())
+        .max()
+        .unwrap_or(0);
+    proposals.iter().take(words).for_each(
+        |((word, lemma, category, freq, _, _), submissions, kinds)| {
+            println!(
+                "{:<width$} {:>5} submissions, freq {:>5}, {} ({}) {:?}",
+                word,
+                submissions,
+                freq,
+                lemma,
+                category.nombre(),
+                kinds
+            )
+        },
+    );
+
+    if let Some(output) = output {
+        let list: String = proposals
+            .iter()
+            .map(|(record, ..)| format!("{}\n", record.0))
+            .collect();
+        std::fs::write(&output, list)
+            .unwrap_or_else(|_| panic!("Unable to write {}", output.display()));
+        println!("Wrote the proposals to {}", output.display());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tally_rejections() {
+        let log = concat!(
+            r#"{"word":"dudar","language":"es","day":19800,"near_miss":{"kind":"letter-not-in-hive","letters":["a","r"],"in_word_list":true}}"#,
+            "\n",
+            r#"{"word":"sudoku","language":"es","day":19800,"near_miss":{"kind":"letter-not-in-hive","letters":["k"],"in_word_list":false}}"#,
+            "\n",
+            r#"{"word":"sudoku","language":"es","day":19801,"near_miss":null}"#,
+            "\n",
+            r#"{"word":"sudoku","language":"pt","day":19801,"near_miss":null}"#,
+            "\n",
+            "not a rejection\n",
+        );
+        let mut rejections = HashMap::new();
+        tally(log, Language::Es, &mut rejections);
+        tally(log, Language::Es, &mut rejections);
+
+        assert_eq!(rejections.len(), 1);
+        let (submissions, kinds) = &rejections["sudoku"];
+        assert_eq!(*submissions, 4);
+        assert_eq!(kinds.iter().collect::<Vec<_>>(), ["letter-not-in-hive"]);
+    }
+}
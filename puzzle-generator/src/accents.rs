@@ -0,0 +1,222 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use crate::puzzles::Puzzle;
+
+/// Extra points for typing a word's accents, with [AccentPolicy::AccentBonus]
+pub const ACCENT_BONUS: u32 = 2;
+
+/// How the accented variants of a word, e.g. "papa" and "papá", are awarded
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AccentPolicy {
+    /// Typing the word with or without accents awards all of its variants
+    #[default]
+    AwardAll,
+    /// Typing the word awards only its most frequent variant. Without the
+    /// metadata to compare them by, the unaccented variant is awarded, or the
+    /// first in alphabetical order if every variant is accented.
+    MostFrequent,
+    /// Each variant is awarded only when typed with its exact accents
+    RequireAccent,
+    /// Like [AccentPolicy::AwardAll], plus [ACCENT_BONUS] points for typing
+    /// an accented variant with its accents
+    AccentBonus,
+}
+
+impl AccentPolicy {
+    pub const ALL: [AccentPolicy; 4] = [
+        AccentPolicy::AwardAll,
+        AccentPolicy::MostFrequent,
+        AccentPolicy::RequireAccent,
+        AccentPolicy::AccentBonus,
+    ];
+
+    /// The variants awarded for typing a word, given the variants of its
+    /// normalized form, and the bonus points for it. `None` if the word must
+    /// be typed with other accents.
+    pub fn award(
+        &self,
+        puzzle: &Puzzle,
+        typed: &str,
+        variants: &HashSet<String>,
+    ) -> Option<(Vec<String>, u32)> {
+        let mut all: Vec<String> = variants.iter().cloned().collect();
+        all.sort();
+        match self {
+            AccentPolicy::AwardAll => Some((all, 0)),
+            AccentPolicy::MostFrequent => Some((
+                most_frequent(puzzle, variants)
+                    .into_iter()
+                    .cloned()
+                    .collect(),
+                0,
+            )),
+            AccentPolicy::RequireAccent => variants
+                .contains(typed)
+                .then(|| (vec![typed.to_string()], 0)),
+            AccentPolicy::AccentBonus => {
+                let accented = variants.contains(typed) && is_accented(puzzle, typed);
+                Some((all, if accented { ACCENT_BONUS } else { 0 }))
+            }
+        }
+    }
+
+    /// The variants that can be found, sorted
+    pub fn findable<'a>(&self, puzzle: &'a Puzzle) -> Vec<&'a String> {
+        let mut findable: Vec<&String> = match self {
+            AccentPolicy::MostFrequent => puzzle
+                .normalized_words()
+                .filter_map(|word| most_frequent(puzzle, puzzle.forms_of_word(word)?))
+                .collect(),
+            _ => puzzle.all_words().collect(),
+        };
+        findable.sort();
+        findable
+    }
+
+    /// The score of finding all of the findable variants, with the highest
+    /// bonus for each word
+    pub fn max_score(&self, puzzle: &Puzzle) -> u32 {
+        let score: u32 = self
+            .findable(puzzle)
+            .into_iter()
+            .map(|word| puzzle.score(word))
+            .sum();
+        let bonus = match self {
+            AccentPolicy::AccentBonus => {
+                let accented = puzzle
+                    .normalized_words()
+                    .filter(|word| {
+                        puzzle.forms_of_word(word).is_some_and(|variants| {
+                            variants.iter().any(|form| is_accented(puzzle, form))
+                        })
+                    })
+                    .count();
+                accented as u32 * ACCENT_BONUS
+            }
+            _ => 0,
+        };
+        score + bonus
+    }
+}
+
+/// Points for typing a form with its accents in strict mode, where each
+/// variant must be typed exactly
+pub fn strict_bonus(puzzle: &Puzzle, form: &str) -> u32 {
+    match is_accented(puzzle, form) {
+        true => ACCENT_BONUS,
+        false => 0,
+    }
+}
+
+/// The score of finding all of the variants in strict mode
+pub fn strict_max_score(puzzle: &Puzzle) -> u32 {
+    puzzle
+        .all_words()
+        .map(|word| puzzle.score(word) + strict_bonus(puzzle, word))
+        .sum()
+}
+
+fn is_accented(puzzle: &Puzzle, form: &str) -> bool {
+    puzzle.language().normalize(form) != form
+}
+
+/// The most frequent variant if the word list has metadata, otherwise the
+/// unaccented one, or the first in alphabetical order, see
+/// [AccentPolicy::MostFrequent]
+fn most_frequent<'a>(puzzle: &Puzzle, variants: &'a HashSet<String>) -> Option<&'a String> {
+    variants.iter().max_by(|a, b| {
+        let frequency = |form: &str| puzzle.info(form).map_or(0.0, |info| info.frequency);
+        frequency(a)
+            .total_cmp(&frequency(b))
+            .then(is_accented(puzzle, b).cmp(&is_accented(puzzle, a)))
+            .then(b.cmp(a))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{puzzles, testing};
+
+    #[test]
+    fn awarded_variants() {
+        let puzzle = testing::puzzle();
+        let variants = puzzle.forms_of_word("dudo").unwrap();
+        let award = |policy: AccentPolicy, typed| policy.award(&puzzle, typed, variants);
+        let both = vec!["dudo".to_string(), "dudó".to_string()];
+
+        assert_eq!(
+            award(AccentPolicy::AwardAll, "dudo"),
+            Some((both.clone(), 0))
+        );
+        assert_eq!(
+            award(AccentPolicy::RequireAccent, "dudó"),
+            Some((vec!["dudó".into()], 0))
+        );
+        assert_eq!(award(AccentPolicy::RequireAccent, "dúdo"), None);
+        assert_eq!(
+            award(AccentPolicy::AccentBonus, "dudo"),
+            Some((both.clone(), 0))
+        );
+        assert_eq!(
+            award(AccentPolicy::AccentBonus, "dudó"),
+            Some((both, ACCENT_BONUS))
+        );
+    }
+
+    #[test]
+    fn most_frequent_without_metadata_is_unaccented() {
+        let puzzle = testing::puzzle();
+        let variants = puzzle.forms_of_word("dudo").unwrap();
+        let awarded = AccentPolicy::MostFrequent.award(&puzzle, "dudó", variants);
+        assert_eq!(awarded, Some((vec!["dudo".into()], 0)));
+
+        // One variant of each word
+        let findable = AccentPolicy::MostFrequent.findable(&puzzle);
+        assert_eq!(findable.len(), puzzle.normalized_words().count());
+        assert!(findable.iter().any(|word| *word == "dudo"));
+        assert!(!findable.iter().any(|word| *word == "dudó"));
+    }
+
+    #[test]
+    fn most_frequent_with_metadata() {
+        let dictionary = testing::dictionary();
+        let letters = "dabcefg".chars().collect();
+        let puzzle = puzzles::create_puzzle_in(&dictionary, letters, 0, usize::MAX).unwrap();
+        let variants = puzzle.forms_of_word("dabcefg").unwrap();
+        let awarded = AccentPolicy::MostFrequent.award(&puzzle, "dabcefg", variants);
+        assert_eq!(awarded, Some((vec!["dábcefg".into()], 0)));
+    }
+
+    #[test]
+    fn max_scores() {
+        let puzzle = testing::puzzle();
+        let all = AccentPolicy::AwardAll.max_score(&puzzle);
+        assert_eq!(
+            all,
+            puzzle
+                .all_words()
+                .map(|word| puzzle.score(word))
+                .sum::<u32>()
+        );
+        assert_eq!(AccentPolicy::RequireAccent.max_score(&puzzle), all);
+        assert!(AccentPolicy::MostFrequent.max_score(&puzzle) < all);
+        // Words with an accented variant are worth a bonus once
+        let accented = puzzle
+            .normalized_words()
+            .filter(|word| {
+                puzzle
+                    .forms_of_word(word)
+                    .unwrap()
+                    .iter()
+                    .any(|f| f != *word)
+            })
+            .count() as u32;
+        assert_eq!(
+            AccentPolicy::AccentBonus.max_score(&puzzle),
+            all + accented * ACCENT_BONUS
+        );
+    }
+}
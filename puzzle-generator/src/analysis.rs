@@ -0,0 +1,213 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+use crate::puzzles::Puzzle;
+
+/// A question about a puzzle's words
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "query", rename_all = "kebab-case")]
+pub enum Query {
+    /// Words starting with a prefix, e.g. `ca`
+    Prefix { prefix: String },
+    /// Words with a number of letters
+    Length { length: usize },
+    /// Forms of a lemma, given the lemma or any of its forms
+    Lemma { lemma: String },
+    /// Words containing all of some letters, e.g. `xy`
+    Letters { letters: String },
+    /// Words not found yet
+    Remaining {
+        /// Found words, accented or not
+        #[serde(default)]
+        found: Vec<String>,
+    },
+    /// Counts, lengths, and letter frequencies of the words
+    Stats,
+}
+
+/// The answer to a [Query]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum Answer {
+    Words(Vec<String>),
+    Statistics(Statistics),
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Statistics {
+    pub words: usize,
+    pub lemmas: usize,
+    pub pangrams: usize,
+    pub average_length: f32,
+    /// Map length -> number of words
+    pub lengths: BTreeMap<usize, usize>,
+    /// Map letter -> number of times it is used across the words
+    pub letter_frequency: BTreeMap<char, usize>,
+}
+
+/// Queries over a puzzle's words. Words are matched by their normalized form,
+/// so `ca` matches "cámara", and answers are sorted (*not* normalized) words.
+pub struct PuzzleAnalysis<'a> {
+    puzzle: &'a Puzzle,
+}
+
+impl<'a> PuzzleAnalysis<'a> {
+    pub fn new(puzzle: &'a Puzzle) -> PuzzleAnalysis<'a> {
+        PuzzleAnalysis { puzzle }
+    }
+
+    pub fn answer(&self, query: &Query) -> Answer {
+        match query {
+            Query::Prefix { prefix } => Answer::Words(self.with_prefix(prefix)),
+            Query::Length { length } => Answer::Words(self.with_length(*length)),
+            Query::Lemma { lemma } => Answer::Words(self.of_lemma(lemma)),
+            Query::Letters { letters } => Answer::Words(self.using_letters(letters)),
+            Query::Remaining { found } => Answer::Words(self.remaining(found)),
+            Query::Stats => Answer::Statistics(self.statistics()),
+        }
+    }
+
+    pub fn with_prefix(&self, prefix: &str) -> Vec<String> {
+        let prefix = self.normalize(prefix);
+        self.words_where(|word| word.starts_with(&prefix))
+    }
+
+    pub fn with_length(&self, length: usize) -> Vec<String> {
+        self.words_where(|word| word.chars().count() == length)
+    }
+
+    pub fn of_lemma(&self, lemma: &str) -> Vec<String> {
+        let lemma = self
+            .puzzle
+            .lemma(lemma)
+            .map_or(lemma, |lemma| lemma.as_str());
+        let mut forms: Vec<String> = self
+            .puzzle
+            .forms_of(lemma)
+            .map_or_else(Vec::new, |forms| forms.iter().cloned().collect());
+        forms.sort();
+        forms
+    }
+
+    pub fn using_letters(&self, letters: &str) -> Vec<String> {
+        let letters = self.normalize(letters);
+        self.words_where(|word| letters.chars().all(|c| word.contains(c)))
+    }
+
+    /// Words whose normalized form has not been found
+    pub fn remaining(&self, found: &[String]) -> Vec<String> {
+        let found: HashSet<String> = found.iter().map(|word| self.normalize(word)).collect();
+        self.words_where(|word| !found.contains(word))
+    }
+
+    pub fn statistics(&self) -> Statistics {
+        let mut lengths: BTreeMap<usize, usize> = BTreeMap::new();
+        let mut letter_frequency: BTreeMap<char, usize> = BTreeMap::new();
+        let mut total_length = 0;
+        self.puzzle.all_words().for_each(|word| {
+            let length = word.chars().count();
+            total_length += length;
+            *lengths.entry(length).or_default() += 1;
+            self.normalize(word)
+                .chars()
+                .for_each(|c| *letter_frequency.entry(c).or_default() += 1);
+        });
+
+        let words = self.puzzle.word_count();
+        Statistics {
+            words,
+            lemmas: self.puzzle.lemma_count(),
+            pangrams: self.puzzle.pangrams().len(),
+            average_length: total_length as f32 / words.max(1) as f32,
+            lengths,
+            letter_frequency,
+        }
+    }
+
+    /// The sorted words whose normalized form matches a predicate
+    fn words_where(&self, predicate: impl Fn(&str) -> bool) -> Vec<String> {
+        let mut words: Vec<String> = self
+            .puzzle
+            .all_words()
+            .filter(|word| predicate(&self.normalize(word)))
+            .cloned()
+            .collect();
+        words.sort();
+        words
+    }
+
+    fn normalize(&self, word: &str) -> String {
+        self.puzzle.language().normalize(&word.to_lowercase())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    #[test]
+    fn words_are_matched_normalized() {
+        let puzzle = testing::puzzle();
+        let analysis = PuzzleAnalysis::new(&puzzle);
+        let dud = [
+            "dude", "dudes", "dudo", "dudoso", "dudosos", "dudé", "dudéis", "dudó",
+        ];
+        assert_eq!(analysis.with_prefix("DUD"), dud);
+        assert_eq!(analysis.with_prefix("dúd"), dud);
+        assert_eq!(
+            analysis.with_length(8),
+            ["deseosos", "desoídos", "seguidos"]
+        );
+        assert_eq!(
+            analysis.using_letters("GS"),
+            ["desdigo", "godos", "seguid", "seguido", "seguidos"]
+        );
+    }
+
+    #[test]
+    fn forms_of_a_lemma() {
+        let puzzle = testing::puzzle();
+        let analysis = PuzzleAnalysis::new(&puzzle);
+        let dudar = ["dude", "dudes", "dudo", "dudé", "dudéis", "dudó"];
+        assert_eq!(analysis.of_lemma("dudar"), dudar);
+        assert_eq!(analysis.of_lemma("dudéis"), dudar);
+        assert!(analysis.of_lemma("volar").is_empty());
+    }
+
+    #[test]
+    fn remaining_words() {
+        let puzzle = testing::puzzle();
+        let analysis = PuzzleAnalysis::new(&puzzle);
+        // Both forms of dudo are found by either
+        let remaining = analysis.remaining(&["DUDO".into(), "seguido".into()]);
+        assert_eq!(remaining.len(), puzzle.word_count() - 3);
+        assert!(!remaining
+            .iter()
+            .any(|word| ["dudo", "dudó", "seguido"].contains(&word.as_str())));
+    }
+
+    #[test]
+    fn answers() {
+        let puzzle = testing::puzzle();
+        let analysis = PuzzleAnalysis::new(&puzzle);
+        let query: Query = serde_json::from_str(r#"{"query": "lemma", "lemma": "godo"}"#).unwrap();
+        assert!(
+            matches!(analysis.answer(&query), Answer::Words(words) if words == ["godo", "godos"])
+        );
+
+        let Answer::Statistics(statistics) = analysis.answer(&Query::Stats) else {
+            panic!("Stats answered with words");
+        };
+        assert_eq!(statistics.words, puzzle.word_count());
+        assert_eq!(statistics.lemmas, puzzle.lemma_count());
+        assert_eq!(statistics.pangrams, 2);
+        assert_eq!(
+            statistics.lengths.values().sum::<usize>(),
+            puzzle.word_count()
+        );
+        assert_eq!(statistics.lengths[&8], 3);
+        // Every word has a d, most of them more than one
+        assert!(statistics.letter_frequency[&'d'] > puzzle.word_count());
+    }
+}
@@ -0,0 +1,158 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use words::Language;
+
+use crate::{
+    accents::AccentPolicy,
+    puzzles::{self, Puzzle, MIN_WORDS},
+    scoring::ScoringPreset,
+    Error,
+};
+
+/// Why letters do not make a valid puzzle
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize, Serialize, thiserror::Error)]
+#[serde(tag = "code", rename_all = "kebab-case")]
+pub enum InvalidPuzzle {
+    #[error("The letters must be 7 different letters")]
+    NotSevenLetters,
+    #[error("The center must be one of the letters")]
+    CenterNotInLetters,
+    #[error("No word uses all of the letters")]
+    NoPangram,
+    #[error("The letters make too few words")]
+    TooFewWords,
+}
+
+/// Options for creating a puzzle from a player's letters
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CustomOptions {
+    pub language: Language,
+    /// Puzzles with fewer words are rejected
+    pub min_words: usize,
+    pub scoring: ScoringPreset,
+    pub accents: AccentPolicy,
+}
+
+impl Default for CustomOptions {
+    fn default() -> Self {
+        CustomOptions {
+            language: Language::default(),
+            min_words: MIN_WORDS,
+            scoring: ScoringPreset::default(),
+            accents: AccentPolicy::default(),
+        }
+    }
+}
+
+/// Normalize and validate letters and a center typed by a player, returning
+/// the letters with the center first
+pub fn puzzle_letters(
+    language: Language,
+    letters: &str,
+    center: &str,
+) -> Result<Vec<char>, InvalidPuzzle> {
+    let normalize = |s: &str| language.normalize(&s.trim().to_lowercase());
+    let letters: Vec<char> = normalize(letters).chars().collect();
+    let letter_set: HashSet<char> = HashSet::from_iter(letters.iter().copied());
+    if letters.len() != 7
+        || letter_set.len() != 7
+        || !letters.iter().all(|c| c.is_ascii_lowercase())
+    {
+        return Err(InvalidPuzzle::NotSevenLetters);
+    }
+
+    let center: Vec<char> = normalize(center).chars().collect();
+    let [center] = center[..] else {
+        return Err(InvalidPuzzle::CenterNotInLetters);
+    };
+    if !letter_set.contains(&center) {
+        return Err(InvalidPuzzle::CenterNotInLetters);
+    }
+
+    let mut ordered = vec![center];
+    ordered.extend(letters.iter().filter(|&&c| c != center));
+    Ok(ordered)
+}
+
+/// Create a puzzle from a player's letters. Unlike daily puzzles there is no
+/// upper bound on the number of words, but there must be a pangram.
+pub fn custom_puzzle(letters: &str, center: &str, options: CustomOptions) -> Result<Puzzle, Error> {
+    let letters = puzzle_letters(options.language, letters, center)?;
+    let puzzle = puzzles::create_puzzle_with_bounds(options.language, letters, 0, usize::MAX)?;
+    if puzzle.pangrams().is_empty() {
+        return Err(InvalidPuzzle::NoPangram.into());
+    }
+    if puzzle.word_count() < options.min_words {
+        return Err(InvalidPuzzle::TooFewWords.into());
+    }
+    Ok(puzzle
+        .with_scoring(options.scoring)
+        .with_accents(options.accents))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    #[test]
+    fn letters_are_normalized() {
+        assert_eq!(
+            puzzle_letters(Language::Es, "dsegiou", " Ó").unwrap(),
+            ['o', 'd', 's', 'e', 'g', 'i', 'u']
+        );
+        assert_eq!(
+            puzzle_letters(Language::Es, " SÉGIDOU ", "D").unwrap(),
+            ['d', 's', 'e', 'g', 'i', 'o', 'u']
+        );
+    }
+
+    #[test]
+    fn invalid_letters() {
+        let letters = |letters, center| puzzle_letters(Language::Es, letters, center);
+        assert_eq!(letters("dsegio", "d"), Err(InvalidPuzzle::NotSevenLetters));
+        assert_eq!(letters("dsegioo", "d"), Err(InvalidPuzzle::NotSevenLetters));
+        assert_eq!(letters("dsegio1", "d"), Err(InvalidPuzzle::NotSevenLetters));
+        assert_eq!(
+            letters("dsegiou", "a"),
+            Err(InvalidPuzzle::CenterNotInLetters)
+        );
+        assert_eq!(
+            letters("dsegiou", "ds"),
+            Err(InvalidPuzzle::CenterNotInLetters)
+        );
+    }
+
+    #[test]
+    fn custom_puzzles() {
+        let options = CustomOptions {
+            scoring: ScoringPreset::Flat,
+            accents: AccentPolicy::RequireAccent,
+            ..Default::default()
+        };
+        let puzzle = custom_puzzle("DSEGIOU", "d", options).unwrap();
+        assert_eq!(puzzle.letters(), testing::puzzle().letters());
+        assert_eq!(puzzle.word_count(), testing::puzzle().word_count());
+        assert_eq!(puzzle.scoring(), ScoringPreset::Flat);
+        assert_eq!(puzzle.accents(), AccentPolicy::RequireAccent);
+        assert_eq!(puzzle.day(), None);
+    }
+
+    #[test]
+    fn rejected_puzzles() {
+        let rejection = |letters, options| match custom_puzzle(letters, "d", options) {
+            Err(Error::InvalidPuzzle(invalid)) => invalid,
+            other => panic!("{} made {:?}", letters, other.map(|p| p.word_count())),
+        };
+        assert_eq!(
+            rejection("bcdfghj", CustomOptions::default()),
+            InvalidPuzzle::NoPangram
+        );
+        let options = CustomOptions {
+            min_words: testing::puzzle().word_count() + 1,
+            ..Default::default()
+        };
+        assert_eq!(rejection("dsegiou", options), InvalidPuzzle::TooFewWords);
+    }
+}
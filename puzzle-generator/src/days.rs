@@ -0,0 +1,197 @@
+use jiff::{
+    civil::{self, Date},
+    tz::{Offset, TimeZone},
+    Timestamp,
+};
+use serde::Serialize;
+use std::{fmt, str::FromStr};
+
+use crate::Error;
+
+/// The day of the first puzzle, #1: 2024-06-11, when the game was released.
+/// The calendar still lets the days before it be played, but their puzzles
+/// have no number.
+pub const LAUNCH_DAY: u32 = 19_885;
+
+/// Why a day or puzzle number cannot be played
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, thiserror::Error)]
+#[serde(tag = "code", rename_all = "kebab-case")]
+pub enum DayError {
+    #[error("Day {day} is before the first puzzle, on day {}", LAUNCH_DAY)]
+    BeforeLaunch { day: u32 },
+    #[error("Day {day} is after today, day {today}")]
+    InFuture { day: u32, today: u32 },
+    #[error("Puzzles are numbered from 1")]
+    NumberZero,
+}
+
+/// The time zone the puzzle days roll over in. Day 0 is 1970-01-01 in the
+/// zone, and each day starts at midnight of the zone's local time, e.g. at
+/// 00:00 CET or CEST in `Europe/Madrid`. On days when a daylight saving
+/// change skips midnight, the day starts at the first local time after it.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum DayZone {
+    /// Days roll over at 00:00 UTC, as they always have
+    #[default]
+    Utc,
+    /// A fixed offset from UTC in seconds, e.g. `-06:00`
+    Offset(i32),
+    /// An IANA time zone, e.g. `America/Mexico_City`
+    Named(String),
+}
+
+impl DayZone {
+    fn time_zone(&self) -> Result<TimeZone, Error> {
+        match self {
+            DayZone::Utc => Ok(TimeZone::UTC),
+            DayZone::Offset(seconds) => Offset::from_seconds(*seconds)
+                .map(TimeZone::fixed)
+                .map_err(|_| Error::TimeZone(self.to_string())),
+            DayZone::Named(name) => TimeZone::get(name).map_err(|_| Error::TimeZone(name.clone())),
+        }
+    }
+}
+
+impl FromStr for DayZone {
+    type Err = Error;
+
+    /// Parse `UTC`, an offset like `+01:00` or `-0600`, or an IANA name
+    fn from_str(zone: &str) -> Result<Self, Self::Err> {
+        let zone = zone.trim();
+        if zone.eq_ignore_ascii_case("utc") || zone == "Z" {
+            return Ok(DayZone::Utc);
+        }
+        let sign = match zone.chars().next() {
+            Some('+') => 1,
+            Some('-') => -1,
+            _ => {
+                let zone = DayZone::Named(zone.to_string());
+                zone.time_zone()?;
+                return Ok(zone);
+            }
+        };
+
+        let digits: String = zone[1..].chars().filter(|c| *c != ':').collect();
+        let (hours, minutes) = match digits.len() {
+            1 | 2 => (digits.as_str(), "0"),
+            4 => digits.split_at(2),
+            _ => return Err(Error::TimeZone(zone.to_string())),
+        };
+        let (Ok(hours), Ok(minutes)) = (hours.parse::<i32>(), minutes.parse::<i32>()) else {
+            return Err(Error::TimeZone(zone.to_string()));
+        };
+        if hours > 14 || minutes >= 60 {
+            return Err(Error::TimeZone(zone.to_string()));
+        }
+        Ok(DayZone::Offset(sign * (hours * 3600 + minutes * 60)))
+    }
+}
+
+impl fmt::Display for DayZone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DayZone::Utc => write!(f, "UTC"),
+            DayZone::Offset(seconds) => {
+                let sign = if *seconds < 0 { '-' } else { '+' };
+                let minutes = seconds.abs() / 60;
+                write!(f, "{}{:02}:{:02}", sign, minutes / 60, minutes % 60)
+            }
+            DayZone::Named(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// The first day, 1970-01-01
+const EPOCH: Date = civil::date(1970, 1, 1);
+
+/// The puzzle day of a time, in milliseconds since the Unix epoch, in a zone
+pub fn puzzle_day_for(timestamp: i64, zone: &DayZone) -> Result<u32, Error> {
+    let time_zone = zone.time_zone()?;
+    let date = Timestamp::from_millisecond(timestamp)
+        .map_err(|err| Error::InvalidInput(err.to_string()))?
+        .to_zoned(time_zone)
+        .date();
+    let days = EPOCH
+        .until(date)
+        .map_err(|err| Error::InvalidInput(err.to_string()))?
+        .get_days();
+    u32::try_from(days).map_err(|_| Error::InvalidInput(format!("{} is before 1970", date)))
+}
+
+/// The number of a day's puzzle, counting from #1 on [LAUNCH_DAY], if the day
+/// can be played on day `today`
+pub fn puzzle_number(day: u32, today: u32) -> Result<u32, DayError> {
+    let Some(since_launch) = day.checked_sub(LAUNCH_DAY) else {
+        return Err(DayError::BeforeLaunch { day });
+    };
+    if day > today {
+        return Err(DayError::InFuture { day, today });
+    }
+    Ok(since_launch + 1)
+}
+
+/// The day of a puzzle number, if it can be played on day `today`
+pub fn day_for_puzzle_number(number: u32, today: u32) -> Result<u32, DayError> {
+    let day = number
+        .checked_sub(1)
+        .ok_or(DayError::NumberZero)?
+        .saturating_add(LAUNCH_DAY);
+    puzzle_number(day, today)?;
+    Ok(day)
+}
+
+/// The date of a day
+pub fn date_for_day(day: u32) -> Result<Date, Error> {
+    jiff::Span::new()
+        .try_days(day)
+        .and_then(|days| EPOCH.checked_add(days))
+        .map_err(|err| Error::InvalidInput(err.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn puzzle_numbers() {
+        let today = LAUNCH_DAY + 10;
+        assert_eq!(puzzle_number(LAUNCH_DAY, today), Ok(1));
+        assert_eq!(puzzle_number(today, today), Ok(11));
+        assert_eq!(day_for_puzzle_number(11, today), Ok(today));
+        assert_eq!(
+            puzzle_number(today + 1, today),
+            Err(DayError::InFuture {
+                day: today + 1,
+                today
+            })
+        );
+        assert_eq!(day_for_puzzle_number(0, today), Err(DayError::NumberZero));
+        assert_eq!(
+            day_for_puzzle_number(12, today),
+            Err(DayError::InFuture {
+                day: today + 1,
+                today
+            })
+        );
+    }
+
+    #[test]
+    fn days_before_launch_have_no_number() {
+        assert_eq!(
+            puzzle_number(LAUNCH_DAY - 1, LAUNCH_DAY),
+            Err(DayError::BeforeLaunch {
+                day: LAUNCH_DAY - 1
+            })
+        );
+        assert_eq!(
+            puzzle_number(0, LAUNCH_DAY),
+            Err(DayError::BeforeLaunch { day: 0 })
+        );
+    }
+
+    #[test]
+    fn dates() {
+        assert_eq!(date_for_day(0).unwrap(), EPOCH);
+        assert_eq!(date_for_day(LAUNCH_DAY).unwrap(), civil::date(2024, 6, 11));
+    }
+}
@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use words::WordInfo;
+
+use crate::Error;
+
+/// Words at least this difficult are flagged as obscure
+pub const OBSCURE_DIFFICULTY: f32 = 0.7;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    /// Classify the average difficulty of a puzzle's words
+    pub fn from_score(score: f32) -> Difficulty {
+        if score < 0.3 {
+            Difficulty::Easy
+        } else if score < 0.45 {
+            Difficulty::Normal
+        } else {
+            Difficulty::Hard
+        }
+    }
+
+    /// RNG stream of the daily puzzle of this difficulty, so each difficulty
+    /// gets its own puzzle from the day's seed. The plain daily puzzle uses
+    /// stream 0.
+    pub fn stream(&self) -> u64 {
+        match self {
+            Difficulty::Easy => 1,
+            Difficulty::Normal => 2,
+            Difficulty::Hard => 3,
+        }
+    }
+}
+
+impl std::str::FromStr for Difficulty {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "easy" => Ok(Difficulty::Easy),
+            "normal" => Ok(Difficulty::Normal),
+            "hard" => Ok(Difficulty::Hard),
+            _ => Err(Error::InvalidInput(format!("Unknown difficulty {}", s))),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Rating {
+    /// Average difficulty of the words, from 0 (easy) to 1 (hard)
+    pub score: f32,
+    pub difficulty: Difficulty,
+    /// Words at least [OBSCURE_DIFFICULTY] difficult, sorted
+    pub obscure: Vec<String>,
+}
+
+/// Rate a puzzle from its words' metadata, `None` if there is no metadata
+pub fn rate(info: &HashMap<String, WordInfo>) -> Option<Rating> {
+    if info.is_empty() {
+        return None;
+    }
+
+    let score = info.values().map(|info| info.difficulty).sum::<f32>() / info.len() as f32;
+    let mut obscure: Vec<String> = info
+        .iter()
+        .filter(|(_, info)| info.difficulty >= OBSCURE_DIFFICULTY)
+        .map(|(form, _)| form.clone())
+        .collect();
+    obscure.sort();
+
+    Some(Rating {
+        score,
+        difficulty: Difficulty::from_score(score),
+        obscure,
+    })
+}
@@ -0,0 +1,265 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use miniz_oxide::{deflate::compress_to_vec, inflate::decompress_to_vec_with_limit};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use words::Language;
+
+use crate::{hints::Hint, save::SaveState, share::fnv1a};
+
+/// Version of the export layout, the export's first byte
+const FORMAT: u8 = 1;
+/// Format and checksum, before the compressed bundle
+const HEADER: usize = 1 + 4;
+const COMPRESSION_LEVEL: u8 = 9;
+/// Largest bundle JSON accepted, so that a bad export cannot exhaust memory
+const MAX_JSON_SIZE: usize = 16 << 20;
+
+/// Why an export could not be imported
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, thiserror::Error)]
+#[serde(tag = "code", rename_all = "kebab-case")]
+pub enum ImportError {
+    #[error("The export has characters that are not part of an export")]
+    InvalidCharacters,
+    #[error("The export is too short")]
+    TooShort,
+    #[error("The export was made by a newer version of the game")]
+    UnknownFormat,
+    #[error("The export is corrupted")]
+    Corrupted,
+    #[error("The export does not match its checksum")]
+    BadChecksum,
+}
+
+/// Totals over the save states of a bundle, to show what an import brings
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+pub struct BundleStatistics {
+    pub days_played: usize,
+    pub words_found: usize,
+    /// Days with some progress of each language
+    pub days_by_language: BTreeMap<Language, usize>,
+}
+
+impl BundleStatistics {
+    pub fn from_states(states: &[SaveState]) -> BundleStatistics {
+        let mut statistics = BundleStatistics::default();
+        states
+            .iter()
+            .filter(|state| !state.found.is_empty() || !state.forms.is_empty())
+            .for_each(|state| {
+                statistics.days_played += 1;
+                statistics.words_found += state.found.len() + state.forms.len();
+                *statistics
+                    .days_by_language
+                    .entry(state.language)
+                    .or_default() += 1;
+            });
+        statistics
+    }
+}
+
+/// A player's progress on every day, to move it to another device as a file
+/// or a QR code
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ExportBundle {
+    /// When the bundle was exported, in milliseconds since the Unix epoch
+    pub exported: u64,
+    /// Sorted by language and day
+    pub states: Vec<SaveState>,
+    pub statistics: BundleStatistics,
+}
+
+impl ExportBundle {
+    pub fn new(states: Vec<SaveState>, exported: u64) -> ExportBundle {
+        let states = merge(Vec::new(), states);
+        ExportBundle {
+            exported,
+            statistics: BundleStatistics::from_states(&states),
+            states,
+        }
+    }
+
+    /// The bundle as deflated JSON behind a format byte and a checksum of the
+    /// JSON, as a URL-safe base64 string
+    pub fn encode(&self) -> String {
+        // Serializing plain data cannot fail
+        let json = serde_json::to_vec(self).unwrap_or_default();
+        let mut bytes = Vec::with_capacity(HEADER + json.len() / 4);
+        bytes.push(FORMAT);
+        bytes.extend(fnv1a(&json).to_be_bytes());
+        bytes.extend(compress_to_vec(&json, COMPRESSION_LEVEL));
+        URL_SAFE_NO_PAD.encode(bytes)
+    }
+
+    pub fn decode(export: &str) -> Result<ExportBundle, ImportError> {
+        let bytes = URL_SAFE_NO_PAD
+            .decode(export.trim())
+            .map_err(|_| ImportError::InvalidCharacters)?;
+        if bytes.len() < HEADER {
+            return Err(ImportError::TooShort);
+        }
+        if bytes[0] != FORMAT {
+            return Err(ImportError::UnknownFormat);
+        }
+        let json = decompress_to_vec_with_limit(&bytes[HEADER..], MAX_JSON_SIZE)
+            .map_err(|_| ImportError::Corrupted)?;
+        if fnv1a(&json).to_be_bytes() != bytes[1..HEADER] {
+            return Err(ImportError::BadChecksum);
+        }
+        serde_json::from_slice(&json).map_err(|_| ImportError::Corrupted)
+    }
+
+    /// Merge the bundle into the existing progress
+    pub fn import(self, existing: Vec<SaveState>) -> ExportBundle {
+        ExportBundle::new(merge(existing, self.states), self.exported)
+    }
+}
+
+/// Merge two collections of save states. The states of the same language
+/// and day keep the union of their found words, unless they are of different
+/// puzzles, e.g. because the day was rescheduled, when the one saved last
+/// wins.
+pub fn merge(existing: Vec<SaveState>, imported: Vec<SaveState>) -> Vec<SaveState> {
+    let mut merged: BTreeMap<(Language, u32), SaveState> = BTreeMap::new();
+    existing.into_iter().chain(imported).for_each(|state| {
+        match merged.remove(&(state.language, state.day)) {
+            Some(other) => {
+                let state = merge_state(other, state);
+                merged.insert((state.language, state.day), state);
+            }
+            None => {
+                merged.insert((state.language, state.day), state);
+            }
+        }
+    });
+    merged.into_values().collect()
+}
+
+fn merge_state(a: SaveState, b: SaveState) -> SaveState {
+    let (older, mut newer) = match a.updated <= b.updated {
+        true => (a, b),
+        false => (b, a),
+    };
+    if older.letters != newer.letters {
+        return newer;
+    }
+
+    // Newest first, as in each state
+    older.found.into_iter().for_each(|word| {
+        if !newer.found.contains(&word) {
+            newer.found.push(word);
+        }
+    });
+    let language = newer.language;
+    older.forms.into_iter().for_each(|form| {
+        if !newer.forms.contains(&form) {
+            newer.forms.push(form);
+        }
+    });
+    let found = newer.found.clone();
+    newer
+        .forms
+        .retain(|form| !found.contains(&language.normalize(form)));
+
+    older.hints.into_iter().for_each(|hint| {
+        if !newer.hints.iter().any(|other| same_hint(other, &hint)) {
+            newer.hints.push(hint);
+        }
+    });
+    newer.bonus = newer.bonus.max(older.bonus);
+    newer.started = newer.started.min(older.started);
+    newer
+}
+
+fn same_hint(a: &Hint, b: &Hint) -> bool {
+    a.word == b.word && a.clue.level() == b.clue.level()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    #[test]
+    fn round_trip() {
+        let states = vec![
+            testing::state(19_801, &["dudo", "dudó", "dedos"], 2),
+            testing::state(19_800, &["seguido"], 1),
+            testing::state(19_802, &[], 3),
+        ];
+        let export = ExportBundle::new(states, 4).encode();
+        let bundle = ExportBundle::decode(&format!(" {}\n", export)).unwrap();
+        assert_eq!(bundle.exported, 4);
+        let days: Vec<u32> = bundle.states.iter().map(|state| state.day).collect();
+        assert_eq!(days, [19_800, 19_801, 19_802]);
+        assert_eq!(bundle.states[1].found, ["dudo", "dedos"]);
+        assert_eq!(
+            bundle.statistics,
+            BundleStatistics {
+                days_played: 2,
+                words_found: 3,
+                days_by_language: BTreeMap::from([(Language::Es, 2)]),
+            }
+        );
+    }
+
+    #[test]
+    fn bad_exports() {
+        let export = ExportBundle::new(vec![testing::state(19_800, &["dedo"], 1)], 2).encode();
+        let tamper = |change: &dyn Fn(&mut Vec<u8>)| {
+            let mut bytes = URL_SAFE_NO_PAD.decode(&export).unwrap();
+            change(&mut bytes);
+            ExportBundle::decode(&URL_SAFE_NO_PAD.encode(bytes)).err()
+        };
+        let decode = |export: &str| ExportBundle::decode(export).err();
+        assert_eq!(
+            decode("not an export!"),
+            Some(ImportError::InvalidCharacters)
+        );
+        assert_eq!(decode("AQID"), Some(ImportError::TooShort));
+        assert_eq!(
+            tamper(&|bytes| bytes[0] = FORMAT + 1),
+            Some(ImportError::UnknownFormat)
+        );
+        assert_eq!(
+            tamper(&|bytes| bytes[1] ^= 1),
+            Some(ImportError::BadChecksum)
+        );
+        assert_eq!(
+            tamper(&|bytes| bytes.truncate(HEADER + 2)),
+            Some(ImportError::Corrupted)
+        );
+    }
+
+    #[test]
+    fn imports_merge_days() {
+        let existing = vec![
+            testing::state(19_800, &["dedo", "godo"], 5),
+            testing::state(19_801, &["dedo"], 5),
+        ];
+        let mut rescheduled = testing::state(19_801, &["sodio"], 3);
+        rescheduled.letters = "sdegiou".into();
+        let imported = vec![
+            testing::state(19_800, &["sodio", "dedo"], 6),
+            rescheduled,
+            testing::state(19_802, &["dedo"], 1),
+        ];
+        let bundle = ExportBundle::new(imported, 7).import(existing);
+
+        let found: Vec<(u32, Vec<String>)> = bundle
+            .states
+            .iter()
+            .map(|state| (state.day, state.found.clone()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                // The union, newest first
+                (19_800, vec!["sodio".into(), "dedo".into(), "godo".into()]),
+                // Another puzzle, saved earlier
+                (19_801, vec!["dedo".into()]),
+                (19_802, vec!["dedo".into()]),
+            ]
+        );
+        assert_eq!(bundle.statistics.words_found, 5);
+    }
+}
@@ -0,0 +1,191 @@
+use serde::{Deserialize, Serialize};
+use words::Categoria;
+
+use crate::{puzzles::Puzzle, session::Locale};
+
+/// How much a hint gives away, from weakest to strongest
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HintLevel {
+    FirstLetter,
+    Length,
+    FirstTwoLetters,
+    Lemma,
+    Category,
+}
+
+impl HintLevel {
+    pub const ALL: [HintLevel; 5] = [
+        HintLevel::FirstLetter,
+        HintLevel::Length,
+        HintLevel::FirstTwoLetters,
+        HintLevel::Lemma,
+        HintLevel::Category,
+    ];
+
+    /// Points deducted for the hint, if hints cost points
+    pub fn cost(&self) -> u32 {
+        match self {
+            HintLevel::FirstLetter | HintLevel::Length => 1,
+            HintLevel::FirstTwoLetters | HintLevel::Category => 2,
+            HintLevel::Lemma => 3,
+        }
+    }
+}
+
+/// A clue about an unfound word
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "level", content = "clue", rename_all = "kebab-case")]
+pub enum Clue {
+    FirstLetter(char),
+    Length(usize),
+    FirstTwoLetters(String),
+    Lemma(String),
+    Category(Categoria),
+}
+
+impl Clue {
+    pub fn level(&self) -> HintLevel {
+        match self {
+            Clue::FirstLetter(_) => HintLevel::FirstLetter,
+            Clue::Length(_) => HintLevel::Length,
+            Clue::FirstTwoLetters(_) => HintLevel::FirstTwoLetters,
+            Clue::Lemma(_) => HintLevel::Lemma,
+            Clue::Category(_) => HintLevel::Category,
+        }
+    }
+
+    pub fn text(&self, locale: Locale) -> String {
+        match (self, locale) {
+            (Clue::FirstLetter(c), Locale::Es) => {
+                format!("Empieza con {}", c.to_uppercase())
+            }
+            (Clue::FirstLetter(c), Locale::En) => {
+                format!("It starts with {}", c.to_uppercase())
+            }
+            (Clue::Length(length), Locale::Es) => format!("Tiene {} letras", length),
+            (Clue::Length(length), Locale::En) => format!("It has {} letters", length),
+            (Clue::FirstTwoLetters(start), Locale::Es) => {
+                format!("Empieza con {}", start.to_uppercase())
+            }
+            (Clue::FirstTwoLetters(start), Locale::En) => {
+                format!("It starts with {}", start.to_uppercase())
+            }
+            (Clue::Lemma(lemma), Locale::Es) => format!("Es una forma de {}", lemma),
+            (Clue::Lemma(lemma), Locale::En) => format!("It is a form of {}", lemma),
+            (Clue::Category(category), Locale::Es) => {
+                format!("Su categoría es {}", category.nombre())
+            }
+            (Clue::Category(category), Locale::En) => {
+                format!("Its part of speech is {}", category.name())
+            }
+        }
+    }
+}
+
+/// A hint given during a game
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Hint {
+    /// The (*not* normalized) word the hint is about, kept so that the next
+    /// hint is about the same word
+    pub word: String,
+    pub clue: Clue,
+    pub cost: u32,
+}
+
+/// The clue of a level about a word of a puzzle, if the level says anything
+/// about the word without giving it away
+pub fn clue(puzzle: &Puzzle, word: &str, level: HintLevel) -> Option<Clue> {
+    let language = puzzle.language();
+    let normalized = language.normalize(word);
+    match level {
+        HintLevel::FirstLetter => normalized.chars().next().map(Clue::FirstLetter),
+        HintLevel::Length => Some(Clue::Length(word.chars().count())),
+        HintLevel::FirstTwoLetters => {
+            Some(Clue::FirstTwoLetters(normalized.chars().take(2).collect()))
+        }
+        HintLevel::Lemma => puzzle
+            .lemma(word)
+            .filter(|lemma| language.normalize(lemma) != normalized)
+            .map(|lemma| Clue::Lemma(lemma.clone())),
+        HintLevel::Category => puzzle
+            .info(word)
+            .map(|info| info.category)
+            .filter(|category| !matches!(category, Categoria::U | Categoria::Y))
+            .map(Clue::Category),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{puzzles, session::GameSession, testing};
+
+    #[test]
+    fn clues() {
+        let puzzle = testing::puzzle();
+        let clue_of = |word, level| clue(&puzzle, word, level);
+        assert_eq!(
+            clue_of("dudéis", HintLevel::FirstLetter),
+            Some(Clue::FirstLetter('d'))
+        );
+        assert_eq!(clue_of("dudéis", HintLevel::Length), Some(Clue::Length(6)));
+        assert_eq!(
+            clue_of("dúos", HintLevel::FirstTwoLetters),
+            Some(Clue::FirstTwoLetters("du".into()))
+        );
+        assert_eq!(
+            clue_of("dudéis", HintLevel::Lemma),
+            Some(Clue::Lemma("dudar".into()))
+        );
+        // The lemma of a lemma would give it away
+        assert_eq!(clue_of("dedo", HintLevel::Lemma), None);
+        // The word list has no categories
+        assert_eq!(clue_of("dedo", HintLevel::Category), None);
+
+        let dictionary = testing::dictionary();
+        let letters = "abcdefg".chars().collect();
+        let puzzle = puzzles::create_puzzle_in(&dictionary, letters, 0, usize::MAX).unwrap();
+        let category = clue(&puzzle, "bacdefg", HintLevel::Category).unwrap();
+        assert_eq!(category, Clue::Category(Categoria::V));
+        assert_eq!(category.text(Locale::Es), "Su categoría es verbo");
+        assert_eq!(category.text(Locale::En), "Its part of speech is verb");
+    }
+
+    #[test]
+    fn hints_get_stronger_then_move_on() {
+        let mut session = GameSession::new(testing::puzzle());
+        let mut hint = || {
+            session
+                .hint()
+                .map(|hint| (hint.word.clone(), hint.clue.clone()))
+        };
+        // The shortest words first, with the clues that say something
+        assert_eq!(hint(), Some(("dedo".into(), Clue::FirstLetter('d'))));
+        assert_eq!(hint(), Some(("dedo".into(), Clue::Length(4))));
+        assert_eq!(
+            hint(),
+            Some(("dedo".into(), Clue::FirstTwoLetters("de".into())))
+        );
+        assert_eq!(hint(), Some(("deis".into(), Clue::FirstLetter('d'))));
+
+        // Once the word is found the next hint is about another
+        session.found.push("deis".into());
+        let hint = session.hint().unwrap();
+        assert_eq!(hint.word, "deos");
+        assert_eq!(hint.clue.text(Locale::En), "It starts with D");
+    }
+
+    #[test]
+    fn hint_costs() {
+        let mut session = GameSession::new(testing::puzzle());
+        session.found = vec!["seguido".into()];
+        session.hint();
+        session.hint();
+        session.hint();
+        assert_eq!(session.hint_cost(), 4);
+        assert_eq!(session.score(), 14);
+        session.deduct_hint_costs = true;
+        assert_eq!(session.score(), 10);
+    }
+}
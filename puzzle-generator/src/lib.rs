@@ -1,6 +1,25 @@
 use wasm_bindgen::prelude::*;
 
+pub mod accents;
+pub mod analysis;
+pub mod custom;
+pub mod days;
+pub mod difficulty;
+pub mod export;
+pub mod hints;
+pub mod near_miss;
 pub mod puzzles;
+pub mod quality;
+pub mod rotation;
+pub mod save;
+pub mod schedule;
+pub mod scoring;
+pub mod session;
+pub mod share;
+pub mod stats;
+pub mod submission;
+#[cfg(test)]
+mod testing;
 pub mod utils;
 
 #[derive(Debug, thiserror::Error)]
@@ -9,24 +28,404 @@ pub enum Error {
     Io(#[from] std::io::Error),
     #[error(transparent)]
     Serde(#[from] serde_json::error::Error),
+    /// An argument that is not valid, e.g. an unknown difficulty
     #[error("{0}")]
-    Message(String),
-    #[error("BadPuzzle: {0}")]
-    BadPuzzle(String),
+    InvalidInput(String),
+    #[error("Too many tries ({tries}), failed to find puzzle")]
+    Exhausted { tries: usize },
+    #[error("BadPuzzle: {reason}")]
+    BadPuzzle {
+        reason: String,
+        letters: Vec<char>,
+        words: usize,
+    },
+    #[error("No word list for language {0}")]
+    UnsupportedLanguage(String),
+    #[error(transparent)]
+    Day(#[from] days::DayError),
+    #[error("Unknown time zone {0}")]
+    TimeZone(String),
+    #[error("Made with word list {found}, but the word list is {expected}")]
+    VersionMismatch { expected: String, found: String },
+    #[error(transparent)]
+    InvalidPuzzle(#[from] custom::InvalidPuzzle),
+    #[error(transparent)]
+    ShareCode(#[from] share::ShareCodeError),
+    #[error(transparent)]
+    Import(#[from] export::ImportError),
+}
+
+impl Error {
+    /// Code of the error for the frontend, e.g. `unsupported-language`. The
+    /// errors of other modules are coded by their variant, e.g. `bad-checksum`.
+    pub fn code(&self) -> String {
+        let code = match self {
+            Error::Io(_) => "io",
+            Error::Serde(_) => "invalid-json",
+            Error::InvalidInput(_) => "invalid-input",
+            Error::Exhausted { .. } => "exhausted",
+            Error::BadPuzzle { .. } => "bad-puzzle",
+            Error::UnsupportedLanguage(_) => "unsupported-language",
+            Error::Day(invalid) => return tagged(invalid).0,
+            Error::TimeZone(_) => "unknown-time-zone",
+            Error::VersionMismatch { .. } => "version-mismatch",
+            Error::InvalidPuzzle(invalid) => return tagged(invalid).0,
+            Error::ShareCode(invalid) => return tagged(invalid).0,
+            Error::Import(invalid) => return tagged(invalid).0,
+        };
+        code.to_string()
+    }
+
+    /// What the error is about, e.g. the letters of a bad puzzle, or `null`
+    pub fn details(&self) -> serde_json::Value {
+        match self {
+            Error::Exhausted { tries } => serde_json::json!({ "tries": tries }),
+            Error::BadPuzzle { letters, words, .. } => {
+                serde_json::json!({ "letters": letters, "words": words })
+            }
+            Error::UnsupportedLanguage(language) => serde_json::json!({ "language": language }),
+            Error::Day(invalid) => tagged(invalid).1,
+            Error::TimeZone(zone) => serde_json::json!({ "zone": zone }),
+            Error::VersionMismatch { expected, found } => {
+                serde_json::json!({ "expected": expected, "found": found })
+            }
+            Error::InvalidPuzzle(invalid) => tagged(invalid).1,
+            Error::ShareCode(invalid) => tagged(invalid).1,
+            Error::Import(invalid) => tagged(invalid).1,
+            _ => serde_json::Value::Null,
+        }
+    }
 }
 
+/// Split an error of another module, serialized with its variant in kebab
+/// case as its `code`, into its code and its other fields, or `null`
+fn tagged(error: &impl serde::Serialize) -> (String, serde_json::Value) {
+    let Ok(serde_json::Value::Object(mut fields)) = serde_json::to_value(error) else {
+        return (String::new(), serde_json::Value::Null);
+    };
+    let code = match fields.remove("code") {
+        Some(serde_json::Value::String(code)) => code,
+        _ => String::new(),
+    };
+    match fields.is_empty() {
+        true => (code, serde_json::Value::Null),
+        false => (code, serde_json::Value::Object(fields)),
+    }
+}
+
+/// Errors are `{ code, message, details }` objects, with `details` omitted
+/// when there are none
 impl serde::Serialize for Error {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::ser::Serializer,
     {
-        serializer.serialize_str(self.to_string().as_ref())
+        use serde::ser::SerializeMap;
+
+        let details = self.details();
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("code", &self.code())?;
+        map.serialize_entry("message", &self.to_string())?;
+        if !details.is_null() {
+            map.serialize_entry("details", &details)?;
+        }
+        map.end()
     }
 }
 
+/// Parse a language code, defaulting to Spanish. Only languages with a word
+/// list, so far only `es`, are supported.
+fn language(code: Option<String>) -> Result<words::Language, Error> {
+    code.map_or(Ok(words::Language::default()), |code| {
+        words::Language::from_code(&code)
+            .filter(|language| words::dictionary(*language).is_some())
+            .ok_or(Error::UnsupportedLanguage(code))
+    })
+}
+
+/// The day's [puzzles::Puzzle] as JSON. Puzzles of a difficulty are not
+/// offered until the word list has the metadata to rate them by.
 #[wasm_bindgen(js_name = "dailyPuzzle")]
-pub async fn daily_puzzle(day: u32) -> Result<String, String> {
-    puzzles::daily_puzzle(day.into())
+pub async fn daily_puzzle(
+    day: u32,
+    language: Option<String>,
+    accents: Option<String>,
+) -> Result<String, JsValue> {
+    let accents = accents
+        .map(|accents| {
+            serde_json::from_value(serde_json::Value::String(accents.clone()))
+                .map_err(|_| Error::InvalidInput(format!("Unknown accent policy {}", accents)))
+        })
+        .transpose();
+    self::language(language)
+        .and_then(|language| {
+            let options = puzzles::DailyOptions {
+                language,
+                accents: accents?.unwrap_or_default(),
+                ..Default::default()
+            };
+            puzzles::daily_puzzle_with_options(day, options)
+        })
+        .and_then(|puzzle| Ok(serde_json::to_string(&puzzle)?))
+        .map_err(js_error)
+}
+
+/// Build the puzzle of a JSON [schedule::ScheduleEntry]
+#[wasm_bindgen(js_name = "puzzleFromScheduleEntry")]
+pub fn puzzle_from_schedule_entry(entry: String) -> Result<String, JsValue> {
+    serde_json::from_str(&entry)
+        .map_err(Error::from)
+        .and_then(|entry| schedule::puzzle_from_entry(&entry))
+        .and_then(|puzzle| Ok(serde_json::to_string(&puzzle)?))
+        .map_err(js_error)
+}
+
+/// Create a puzzle from a player's letters and center. The options are a JSON
+/// [custom::CustomOptions], and errors are `{ code, message, details }` objects,
+/// e.g. with the code `too-few-words`.
+#[wasm_bindgen(js_name = "customPuzzle")]
+pub fn custom_puzzle(
+    letters: String,
+    center: String,
+    options: Option<String>,
+) -> Result<String, JsValue> {
+    custom_options(options)
+        .and_then(|options| custom::custom_puzzle(&letters, &center, options))
+        .and_then(|puzzle| Ok(serde_json::to_string(&puzzle)?))
+        .map_err(js_error)
+}
+
+/// The share code of the custom puzzle of a player's letters and center, see
+/// [custom_puzzle]
+#[wasm_bindgen(js_name = "shareCode")]
+pub fn share_code(
+    letters: String,
+    center: String,
+    options: Option<String>,
+) -> Result<String, JsValue> {
+    custom_options(options)
+        .and_then(|options| custom::custom_puzzle(&letters, &center, options))
+        .and_then(|puzzle| share::ShareCode::from_puzzle(&puzzle))
+        .map(|code| code.encode())
+        .map_err(js_error)
+}
+
+/// Build the puzzle of a share code. Errors are like [custom_puzzle]'s, e.g.
+/// with the code `bad-checksum`.
+#[wasm_bindgen(js_name = "puzzleFromShareCode")]
+pub fn puzzle_from_share_code(code: String) -> Result<String, JsValue> {
+    share::ShareCode::decode(&code)
+        .map_err(Error::from)
+        .and_then(|code| code.to_puzzle())
         .and_then(|puzzle| Ok(serde_json::to_string(&puzzle)?))
-        .or_else(|err| Err(err.to_string()))
+        .map_err(js_error)
+}
+
+/// The spoiler-free share text of a JSON [session::GameSession], in the
+/// locale `es` (the default) or `en`
+#[wasm_bindgen(js_name = "shareText")]
+pub fn share_text(session: String, locale: Option<String>) -> Result<String, JsValue> {
+    let locale = self::locale(locale).map_err(js_error)?;
+    serde_json::from_str::<session::GameSession>(&session)
+        .map(|session| session.share_text(locale))
+        .map_err(|err| js_error(err.into()))
+}
+
+/// Answer a JSON [analysis::Query] about a JSON puzzle, e.g.
+/// `{ "query": "prefix", "prefix": "ca" }`
+#[wasm_bindgen(js_name = "analyzePuzzle")]
+pub fn analyze_puzzle(puzzle: String, query: String) -> Result<String, JsValue> {
+    serde_json::from_str::<puzzles::Puzzle>(&puzzle)
+        .and_then(|puzzle| {
+            let query: analysis::Query = serde_json::from_str(&query)?;
+            serde_json::to_string(&analysis::PuzzleAnalysis::new(&puzzle).answer(&query))
+        })
+        .map_err(|err| js_error(err.into()))
+}
+
+/// Give the next hint of a JSON [session::GameSession]. Returns the updated
+/// session and the hint, with its clue as text in the locale `es` (the
+/// default) or `en`, or a `null` hint if there are no hints left.
+#[wasm_bindgen(js_name = "nextHint")]
+pub fn next_hint(session: String, locale: Option<String>) -> Result<String, JsValue> {
+    let locale = self::locale(locale).map_err(js_error)?;
+    let mut session: session::GameSession =
+        serde_json::from_str(&session).map_err(|err| js_error(err.into()))?;
+    let hint = session.hint().map(|hint| {
+        serde_json::json!({
+            "clue": hint.clue,
+            "cost": hint.cost,
+            "text": hint.clue.text(locale),
+        })
+    });
+    Ok(serde_json::json!({ "session": session, "hint": hint }).to_string())
+}
+
+/// Submit a word to a JSON [session::GameSession]. Returns the updated session
+/// and the [submission::Feedback], with its text in the locale `es` (the
+/// default) or `en`.
+#[wasm_bindgen(js_name = "submitWord")]
+pub fn submit_word(
+    session: String,
+    word: String,
+    locale: Option<String>,
+) -> Result<String, JsValue> {
+    let locale = self::locale(locale).map_err(js_error)?;
+    let mut session: session::GameSession =
+        serde_json::from_str(&session).map_err(|err| js_error(err.into()))?;
+    let feedback = session.submit(&word);
+    let text = feedback.text(locale);
+    Ok(serde_json::json!({ "session": session, "feedback": feedback, "text": text }).to_string())
+}
+
+/// The found forms of each lemma of a JSON [session::GameSession] with any
+/// found, to group the found words by lemma
+#[wasm_bindgen(js_name = "lemmaProgress")]
+pub fn lemma_progress(session: String) -> Result<String, JsValue> {
+    serde_json::from_str::<session::GameSession>(&session)
+        .and_then(|session| serde_json::to_string(&session.progress_by_lemma()))
+        .map_err(|err| js_error(err.into()))
+}
+
+/// The rejected submissions of a JSON [session::GameSession] as JSON lines
+#[wasm_bindgen(js_name = "rejectionLog")]
+pub fn rejection_log(session: String) -> Result<String, JsValue> {
+    serde_json::from_str::<session::GameSession>(&session)
+        .map(|session| session.rejection_log())
+        .map_err(|err| js_error(err.into()))
+}
+
+/// Save a JSON [session::GameSession] of a daily puzzle as a JSON
+/// [save::SaveState]. The times are milliseconds since the Unix epoch, as
+/// given by `Date.now()`.
+#[wasm_bindgen(js_name = "saveGame")]
+pub fn save_game(session: String, started: f64, updated: f64) -> Result<String, JsValue> {
+    serde_json::from_str::<session::GameSession>(&session)
+        .map_err(Error::from)
+        .and_then(|session| save::SaveState::from_session(&session, started as u64, updated as u64))
+        .and_then(|state| Ok(serde_json::to_string(&state)?))
+        .map_err(js_error)
+}
+
+/// Load a JSON [save::SaveState], or a puzzle saved under `${day}-puzzle`
+/// before save states, as a JSON [session::GameSession] with its puzzle
+/// rebuilt. Returns the session, the save state to replace the stored one,
+/// migrated if it was legacy or saved with another word list, and the found
+/// words dropped because the word list no longer has them.
+#[wasm_bindgen(js_name = "loadGame")]
+pub fn load_game(state: String, now: f64) -> Result<String, JsValue> {
+    let state = save::SaveState::load(&state, now as u64).map_err(js_error)?;
+    let (session, state, dropped) = state.restore().map_err(js_error)?;
+    Ok(serde_json::json!({ "session": session, "state": state, "dropped": dropped }).to_string())
+}
+
+/// Export a JSON array of [save::SaveState]s, e.g. of every day, as a
+/// compressed code to save to a file or show as a QR code
+#[wasm_bindgen(js_name = "exportProgress")]
+pub fn export_progress(states: String, now: f64) -> Result<String, JsValue> {
+    serde_json::from_str(&states)
+        .map(|states| export::ExportBundle::new(states, now as u64).encode())
+        .map_err(|err| js_error(err.into()))
+}
+
+/// Import an exported code into a JSON array of the existing
+/// [save::SaveState]s. Returns the merged [export::ExportBundle], whose states
+/// replace the existing ones.
+#[wasm_bindgen(js_name = "importProgress")]
+pub fn import_progress(export: String, existing: String) -> Result<String, JsValue> {
+    let existing = serde_json::from_str(&existing).map_err(|err| js_error(err.into()))?;
+    let bundle = export::ExportBundle::decode(&export).map_err(|err| js_error(err.into()))?;
+    serde_json::to_string(&bundle.import(existing)).map_err(|err| js_error(err.into()))
+}
+
+/// The [stats::Stats] of a JSON array of [save::SaveState]s of a language,
+/// `es` by default, with `today` the current puzzle day
+#[wasm_bindgen(js_name = "playerStats")]
+pub fn player_stats(
+    states: String,
+    today: u32,
+    language: Option<String>,
+) -> Result<String, JsValue> {
+    let language = self::language(language).map_err(js_error)?;
+    serde_json::from_str::<Vec<save::SaveState>>(&states)
+        .map(|states| stats::Stats::from_states(&states, language, today))
+        .and_then(|stats| serde_json::to_string(&stats))
+        .map_err(|err| js_error(err.into()))
+}
+
+/// The puzzle day of a time, in milliseconds since the Unix epoch as given by
+/// `Date.now()`, in a zone: `UTC` (the default), an offset like `-06:00`, or
+/// an IANA name like `Europe/Madrid`. Days roll over at the zone's midnight.
+#[wasm_bindgen(js_name = "puzzleDayFor")]
+pub fn puzzle_day_for(timestamp: f64, zone: Option<String>) -> Result<u32, JsValue> {
+    zone.map_or(Ok(days::DayZone::default()), |zone| zone.parse())
+        .and_then(|zone| days::puzzle_day_for(timestamp as i64, &zone))
+        .map_err(js_error)
+}
+
+/// The number of a day's puzzle since launch, if the day can be played on
+/// day `today`. Errors are `{ code, message, details }` objects with the code
+/// `before-launch` or `in-future`.
+#[wasm_bindgen(js_name = "puzzleNumber")]
+pub fn puzzle_number(day: u32, today: u32) -> Result<u32, JsValue> {
+    days::puzzle_number(day, today).map_err(|err| js_error(err.into()))
+}
+
+/// The day of a puzzle number, if it can be played on day `today`
+#[wasm_bindgen(js_name = "dayForPuzzleNumber")]
+pub fn day_for_puzzle_number(number: u32, today: u32) -> Result<u32, JsValue> {
+    days::day_for_puzzle_number(number, today).map_err(|err| js_error(err.into()))
+}
+
+/// The date of a day, as `YYYY-MM-DD`
+#[wasm_bindgen(js_name = "dateForDay")]
+pub fn date_for_day(day: u32) -> Result<String, JsValue> {
+    days::date_for_day(day)
+        .map(|date| date.to_string())
+        .map_err(js_error)
+}
+
+/// Parse a locale, defaulting to Spanish
+fn locale(locale: Option<String>) -> Result<session::Locale, Error> {
+    locale.map_or(Ok(session::Locale::default()), |locale| {
+        serde_json::from_value(serde_json::Value::String(locale.clone()))
+            .map_err(|_| Error::InvalidInput(format!("Unknown locale {}", locale)))
+    })
+}
+
+fn custom_options(options: Option<String>) -> Result<custom::CustomOptions, Error> {
+    Ok(options
+        .map(|options| serde_json::from_str(&options))
+        .transpose()?
+        .unwrap_or_default())
+}
+
+/// An error as a JS `{ code, message, details }` object
+fn js_error(err: Error) -> JsValue {
+    use serde::Serialize;
+
+    err.serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+        .unwrap_or_else(|_| JsValue::from_str(&err.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn errors_of_other_modules_are_coded_by_variant() {
+        let error: Error = days::DayError::InFuture { day: 3, today: 2 }.into();
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({
+                "code": "in-future",
+                "message": "Day 3 is after today, day 2",
+                "details": { "day": 3, "today": 2 },
+            })
+        );
+
+        let error: Error = share::ShareCodeError::WrongLength.into();
+        assert_eq!(error.code(), "wrong-length");
+        assert!(error.details().is_null());
+    }
 }
@@ -0,0 +1,168 @@
+use clap::{Parser, Subcommand};
+use puzzle_generator::{
+    analysis::{PuzzleAnalysis, Query},
+    days::DayZone,
+    difficulty::Difficulty,
+    puzzles::{self, DailyOptions, Puzzle},
+    schedule::{self, ScheduleEntry},
+    share::ShareCode,
+    utils, Error,
+};
+use std::{fs, path::PathBuf};
+use words::Language;
+
+/// Program to generate the game's puzzles ahead of time and inspect them
+#[derive(Parser, Debug)]
+struct Args {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Write the daily puzzles of a range of days to a schedule file. Days
+    /// already in the file keep their (possibly hand-edited) entries.
+    Schedule {
+        /// First day, in days since the epoch
+        #[arg(long)]
+        from: u32,
+        /// Last day, inclusive
+        #[arg(long)]
+        to: u32,
+        /// Language of the puzzles. Only es has a word list
+        #[arg(long, default_value_t)]
+        language: Language,
+        /// Difficulty of the puzzles: easy, normal, or hard
+        #[arg(long)]
+        difficulty: Option<Difficulty>,
+        /// Schedule file to write
+        #[arg(long, default_value = "schedule.json")]
+        output: PathBuf,
+    },
+    /// Answer a question about a puzzle's words
+    Analyze {
+        /// Day of the daily puzzle, in days since the epoch. Defaults to today.
+        #[arg(long)]
+        day: Option<u32>,
+        /// Language of the daily puzzle. Only es has a word list
+        #[arg(long, default_value_t)]
+        language: Language,
+        /// Time zone today is in: UTC, an offset like -06:00, or an IANA name
+        /// like America/Mexico_City
+        #[arg(long, default_value = "UTC")]
+        time_zone: DayZone,
+        /// Share code of a puzzle, instead of a daily puzzle
+        #[arg(long, conflicts_with_all = ["day", "language"])]
+        code: Option<String>,
+        #[command(subcommand)]
+        query: QueryCommand,
+    },
+}
+
+/// A question about a puzzle's words, see [Query]
+#[derive(Subcommand, Debug)]
+enum QueryCommand {
+    /// Words starting with a prefix, e.g. `ca`
+    Prefix { prefix: String },
+    /// Words with a number of letters
+    Length { length: usize },
+    /// Forms of a lemma, given the lemma or any of its forms
+    Lemma { lemma: String },
+    /// Words containing all of some letters, e.g. `xy`
+    Letters { letters: String },
+    /// Words not found yet
+    Remaining {
+        /// Found words, accented or not
+        found: Vec<String>,
+    },
+    /// Counts, lengths, and letter frequencies of the words
+    Stats,
+}
+
+impl From<QueryCommand> for Query {
+    fn from(command: QueryCommand) -> Query {
+        match command {
+            QueryCommand::Prefix { prefix } => Query::Prefix { prefix },
+            QueryCommand::Length { length } => Query::Length { length },
+            QueryCommand::Lemma { lemma } => Query::Lemma { lemma },
+            QueryCommand::Letters { letters } => Query::Letters { letters },
+            QueryCommand::Remaining { found } => Query::Remaining { found },
+            QueryCommand::Stats => Query::Stats,
+        }
+    }
+}
+
+fn main() {
+    let args = Args::parse();
+
+    let result = match args.command {
+        Command::Schedule {
+            from,
+            to,
+            language,
+            difficulty,
+            output,
+        } => write_schedule(
+            from,
+            to,
+            DailyOptions {
+                language,
+                difficulty,
+                ..Default::default()
+            },
+            output,
+        ),
+        Command::Analyze {
+            day,
+            language,
+            time_zone,
+            code,
+            query,
+        } => analyze(day, language, time_zone, code, query.into()),
+    };
+
+    if let Err(err) = result {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    }
+}
+
+fn write_schedule(from: u32, to: u32, options: DailyOptions, output: PathBuf) -> Result<(), Error> {
+    let existing: Vec<ScheduleEntry> = match fs::read_to_string(&output) {
+        Ok(json) => serde_json::from_str(&json)?,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+        Err(err) => return Err(err.into()),
+    };
+
+    let entries = schedule::schedule(from, to, options, existing)?;
+    fs::write(&output, serde_json::to_string(&entries)?)?;
+    println!(
+        "Wrote {} scheduled puzzles to {}",
+        entries.len(),
+        output.display()
+    );
+    Ok(())
+}
+
+fn analyze(
+    day: Option<u32>,
+    language: Language,
+    time_zone: DayZone,
+    code: Option<String>,
+    query: Query,
+) -> Result<(), Error> {
+    let puzzle: Puzzle = match code {
+        Some(code) => ShareCode::decode(&code)?.to_puzzle()?,
+        None => {
+            let day = match day {
+                Some(day) => day,
+                None => utils::today(&time_zone)?,
+            };
+            puzzles::daily_puzzle(day, language)?
+        }
+    };
+
+    let answer = PuzzleAnalysis::new(&puzzle).answer(&query);
+    println!("{}", serde_json::to_string_pretty(&answer)?);
+    Ok(())
+}
@@ -0,0 +1,135 @@
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, sync::OnceLock};
+use words::{Dictionary, Language};
+
+use crate::{
+    puzzles::{self, Puzzle},
+    session::Locale,
+};
+
+/// Why a word that is not in a puzzle's list was close
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum NearMiss {
+    /// The word uses letters outside of the hive, and may be in the word list
+    /// of other puzzles
+    LetterNotInHive {
+        letters: Vec<char>,
+        in_word_list: bool,
+    },
+    /// The word is in the list with its accents on other letters
+    WrongAccent { forms: Vec<String> },
+}
+
+impl NearMiss {
+    pub fn text(&self, locale: Locale) -> String {
+        match (self, locale) {
+            (NearMiss::LetterNotInHive { letters, .. }, Locale::Es) => {
+                format!("Usa letras fuera de la colmena: {}", upper(letters))
+            }
+            (NearMiss::LetterNotInHive { letters, .. }, Locale::En) => {
+                format!("Uses letters not in the hive: {}", upper(letters))
+            }
+            (NearMiss::WrongAccent { forms }, Locale::Es) => {
+                format!("Se escribe {}", forms.join(" o "))
+            }
+            (NearMiss::WrongAccent { forms }, Locale::En) => {
+                format!("It is spelled {}", forms.join(" or "))
+            }
+        }
+    }
+}
+
+fn upper(letters: &[char]) -> String {
+    letters.iter().flat_map(|c| c.to_uppercase()).collect()
+}
+
+/// Classify a word (as typed) that is not in the puzzle's list, using the
+/// puzzle's language's whole word list. `None` if nothing is known about the
+/// word.
+pub fn diagnose(puzzle: &Puzzle, word: &str) -> Option<NearMiss> {
+    let language = puzzle.language();
+    let typed = word.trim().to_lowercase();
+    let normalized = language.normalize(&typed);
+    let dictionary = puzzles::dictionary(language).ok()?;
+
+    let hive: HashSet<char> = puzzle
+        .letters()
+        .iter()
+        .flat_map(|c| c.to_lowercase())
+        .collect();
+    let mut outside: Vec<char> = normalized.chars().filter(|c| !hive.contains(c)).collect();
+    outside.sort();
+    outside.dedup();
+    if !outside.is_empty() {
+        return Some(NearMiss::LetterNotInHive {
+            letters: outside,
+            in_word_list: in_word_list(&dictionary, &normalized),
+        });
+    }
+
+    let forms = puzzle.forms_of_word(&normalized)?;
+    if forms.contains(&typed) {
+        return None;
+    }
+    let mut forms: Vec<String> = forms.iter().cloned().collect();
+    forms.sort();
+    Some(NearMiss::WrongAccent { forms })
+}
+
+/// Sorted and deduplicated normalized words of each language's word list, in
+/// the order of [Language::ALL], built the first time they are needed
+static NORMALIZED_WORDS: [OnceLock<Vec<String>>; Language::ALL.len()] =
+    [const { OnceLock::new() }; Language::ALL.len()];
+
+/// Whether any word of the word list has this normalized form
+fn in_word_list(dictionary: &Dictionary, normalized: &str) -> bool {
+    let language = dictionary.language;
+    let Some(index) = Language::ALL.iter().position(|other| *other == language) else {
+        return false;
+    };
+    NORMALIZED_WORDS[index]
+        .get_or_init(|| {
+            let mut words: Vec<String> = dictionary
+                .words
+                .iter()
+                .map(|(form, _)| language.normalize(form))
+                .collect();
+            words.sort();
+            words.dedup();
+            words
+        })
+        .binary_search_by(|word| word.as_str().cmp(normalized))
+        .is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    #[test]
+    fn near_misses() {
+        let puzzle = testing::puzzle();
+        let letters_not_in_hive = |word| match diagnose(&puzzle, word) {
+            Some(NearMiss::LetterNotInHive {
+                letters,
+                in_word_list,
+            }) => (letters, in_word_list),
+            other => panic!("{} is {:?}", word, other),
+        };
+        assert_eq!(letters_not_in_hive("Dudar"), (vec!['a', 'r'], true));
+        assert_eq!(letters_not_in_hive("dudxz"), (vec!['x', 'z'], false));
+
+        let wrong_accent = diagnose(&puzzle, "dúdo").unwrap();
+        assert_eq!(
+            wrong_accent,
+            NearMiss::WrongAccent {
+                forms: vec!["dudo".into(), "dudó".into()]
+            }
+        );
+        assert_eq!(wrong_accent.text(Locale::Es), "Se escribe dudo o dudó");
+        // Nothing is known about made up words of the hive's letters
+        assert_eq!(diagnose(&puzzle, "dosed"), None);
+    }
+}
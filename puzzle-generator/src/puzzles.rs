@@ -2,10 +2,18 @@ use rand::{seq::SliceRandom, SeedableRng};
 use rand_chacha::ChaCha8Rng;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
-use unidecode::unidecode;
 use wasm_bindgen::prelude::*;
+use words::{Language, WordInfo};
 
-use crate::Error;
+use crate::{
+    accents::AccentPolicy,
+    days,
+    difficulty::{self, Difficulty, Rating},
+    quality::{self, CenterEvaluation, CenterPolicy},
+    rotation::Rotation,
+    scoring::ScoringPreset,
+    Error,
+};
 
 #[derive(Clone, Deserialize, Serialize)]
 #[wasm_bindgen]
@@ -16,20 +24,216 @@ pub struct Puzzle {
     forms: HashMap<String, HashSet<String>>,
     pangrams: Vec<String>,
     day: Option<u32>,
+    /// Launch-relative number of a daily puzzle, for players
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    number: Option<u32>,
+    #[serde(default)]
+    language: Language,
+    /// Map form -> category and frequencies, for words with metadata
+    #[serde(default)]
+    info: HashMap<String, WordInfo>,
+    rating: Option<Rating>,
+    /// Evaluations of the letters as the center, for daily puzzles
+    #[serde(default)]
+    centers: Vec<CenterEvaluation>,
+    #[serde(default)]
+    scoring: ScoringPreset,
+    #[serde(default)]
+    accents: AccentPolicy,
 }
 
-pub fn create_puzzle_from_letters(letters: Vec<char>) -> Result<Puzzle, Error> {
-    let all_words = words::palabras::PALABRAS;
+impl Puzzle {
+    /// The lemma of a (*not* normalized) form
+    pub fn lemma(&self, form: &str) -> Option<&String> {
+        self.lemmas.get(form)
+    }
 
-    let letter_set: HashSet<char> = HashSet::from_iter(letters.iter().copied());
+    /// The category and frequencies of a (*not* normalized) form, if the word
+    /// list has metadata
+    pub fn info(&self, form: &str) -> Option<&WordInfo> {
+        self.info.get(form)
+    }
+
+    /// The category and frequencies of the lemma of a (*not* normalized) form,
+    /// if the lemma is itself one of the words
+    pub fn lemma_info(&self, form: &str) -> Option<&WordInfo> {
+        self.info(self.lemma(form)?)
+    }
+
+    /// How difficult the puzzle is and its obscure words, if the word list has
+    /// metadata
+    pub fn rating(&self) -> Option<&Rating> {
+        self.rating.as_ref()
+    }
+
+    /// The letters, center first
+    pub fn letters(&self) -> &[char] {
+        &self.letters
+    }
+
+    /// Number of (*not* normalized) words in the puzzle
+    pub fn word_count(&self) -> usize {
+        self.lemmas.len()
+    }
+
+    pub fn pangrams(&self) -> &[String] {
+        &self.pangrams
+    }
+
+    pub fn language(&self) -> Language {
+        self.language
+    }
+
+    /// The day of a daily puzzle
+    pub fn day(&self) -> Option<u32> {
+        self.day
+    }
+
+    /// The number of a daily puzzle since launch, `None` for puzzles of days
+    /// before it
+    pub fn number(&self) -> Option<u32> {
+        self.number
+    }
+
+    /// The (*not* normalized) forms of a normalized word, e.g. papa -> [papa,
+    /// papá]
+    pub fn forms_of_word(&self, word: &str) -> Option<&HashSet<String>> {
+        self.words.get(word)
+    }
+
+    /// The (*not* normalized) forms of a lemma
+    pub fn forms_of(&self, lemma: &str) -> Option<&HashSet<String>> {
+        self.forms.get(lemma)
+    }
+
+    /// Number of different lemmas of the words
+    pub fn lemma_count(&self) -> usize {
+        self.forms.len()
+    }
+
+    /// All of the normalized words
+    pub fn normalized_words(&self) -> impl Iterator<Item = &String> {
+        self.words.keys()
+    }
+
+    /// All of the (*not* normalized) words
+    pub fn all_words(&self) -> impl Iterator<Item = &String> {
+        self.lemmas.keys()
+    }
+
+    pub fn scoring(&self) -> ScoringPreset {
+        self.scoring
+    }
+
+    /// Score of a (*not* normalized) word of the puzzle
+    pub fn score(&self, word: &str) -> u32 {
+        self.scoring
+            .score(word, self.pangrams.iter().any(|p| p == word))
+    }
+
+    pub fn accents(&self) -> AccentPolicy {
+        self.accents
+    }
+
+    /// Score of finding all of the words that can be found under the accent
+    /// policy
+    pub fn max_score(&self) -> u32 {
+        self.accents.max_score(self)
+    }
+
+    /// The puzzle with another accent policy
+    pub(crate) fn with_accents(mut self, accents: AccentPolicy) -> Puzzle {
+        self.accents = accents;
+        self
+    }
+
+    /// The puzzle scored by another preset
+    pub(crate) fn with_scoring(mut self, scoring: ScoringPreset) -> Puzzle {
+        self.scoring = scoring;
+        self
+    }
+
+    /// The puzzle as the puzzle of a day
+    pub(crate) fn with_day(mut self, day: u32) -> Puzzle {
+        self.day = Some(day);
+        // Puzzles can be made ahead of their day, so only launch matters
+        self.number = days::puzzle_number(day, day).ok();
+        self
+    }
+
+    /// How each of the letters would do as the center, for daily puzzles
+    pub fn centers(&self) -> &[CenterEvaluation] {
+        &self.centers
+    }
+}
 
+/// Default bounds on the number of words in a puzzle
+pub const MIN_WORDS: usize = 25;
+pub const MAX_WORDS: usize = 100;
+/// The first day, 2026-11-01, whose daily puzzle is generated with center
+/// policies. Earlier days keep the puzzles they were first played with.
+pub const CUTOVER_DAY: u32 = 20_758;
+
+/// Sorted letters of the Spanish daily puzzles of the 30 days before
+/// [CUTOVER_DAY], which the first days of the rotation avoid
+pub(crate) const LEGACY_LETTER_SETS: &[&str] = &[
+    "cilmopu", "cilotuv", "ceilmop", "acdijlu", "cdeiosv", "ceiosvx", "aeinstx", "dinosuv",
+    "egimosu", "aeinqtu", "ahiorst", "acimqsu", "degiosu", "acefprt", "acilnoz", "ceilnuy",
+    "dfinotu", "adjnotu", "diopstv", "dgimnos", "adilotu", "degnosu", "bcefino", "efjlors",
+    "aceisuv", "dinosuv", "cdijoru", "aimnstv", "aeijlms", "cfilnot",
+];
+
+pub fn create_puzzle_from_letters(language: Language, letters: Vec<char>) -> Result<Puzzle, Error> {
+    create_puzzle_with_bounds(language, letters, MIN_WORDS, MAX_WORDS)
+}
+
+/// Create a puzzle from letters, with the center letter first, that has
+/// between `min_words` and `max_words` words
+pub fn create_puzzle_with_bounds(
+    language: Language,
+    letters: Vec<char>,
+    min_words: usize,
+    max_words: usize,
+) -> Result<Puzzle, Error> {
+    create_puzzle_in(&dictionary(language)?, letters, min_words, max_words)
+}
+
+/// Create a puzzle out of a word list, see [create_puzzle_with_bounds]
+pub(crate) fn create_puzzle_in(
+    dictionary: &words::Dictionary,
+    letters: Vec<char>,
+    min_words: usize,
+    max_words: usize,
+) -> Result<Puzzle, Error> {
     println!("Trying {:?}", letters);
 
+    let puzzle = build_puzzle(dictionary, dictionary.words.iter(), letters);
+    check_bounds(&puzzle, min_words, max_words)?;
+
+    println!(
+        "Created a puzzle with {} words and {} pangrams: \n\t{:?}",
+        puzzle.word_count(),
+        puzzle.pangrams.len(),
+        puzzle.letters
+    );
+
+    Ok(puzzle)
+}
+
+/// Build the puzzle of the letters, with the center letter first, out of the
+/// candidate words
+fn build_puzzle<'a>(
+    dictionary: &words::Dictionary,
+    candidates: impl Iterator<Item = &'a (&'static str, &'static str)>,
+    letters: Vec<char>,
+) -> Puzzle {
+    let language = dictionary.language;
+    let letter_set: HashSet<char> = HashSet::from_iter(letters.iter().copied());
+
     // Map form -> normalized lema, of all the words in the puzzle
-    let words: HashMap<String, String> = all_words
-        .iter()
+    let words: HashMap<String, String> = candidates
         .filter(|(form, _)| {
-            let form = unidecode(form);
+            let form = language.normalize(form);
 
             let mut contains_center = false;
             form.chars().all(|c| {
@@ -45,46 +249,23 @@ pub fn create_puzzle_from_letters(letters: Vec<char>) -> Result<Puzzle, Error> {
     let pangrams: Vec<String> = words
         .iter()
         .filter(|(form, _)| {
-            let set: HashSet<char> = HashSet::from_iter(unidecode(form).chars());
+            let set: HashSet<char> = HashSet::from_iter(language.normalize(form).chars());
             set.len() == 7
         })
         .map(|(form, _)| form.to_string())
         .collect();
 
-    if words.len() < 25 {
-        println!(
-            "Failed, only found {} words from {:?}",
-            words.len(),
-            letters
-        );
-        return Err(Error::BadPuzzle("Too few words".into()));
-    }
-
-    if words.len() > 100 {
-        println!(
-            "Failed, found too many words ({}) from {:?}",
-            words.len(),
-            letters
-        );
-        return Err(Error::BadPuzzle("Too many words".into()));
-    }
-
-    println!(
-        "Created a puzzle with {} words and {} pangrams: \n\t{:?}",
-        words.len(),
-        pangrams.len(),
-        letters
-    );
-
     // Map normalized word -> denormalized forms, e.g. papa -> [papa, papá]
     let mut accent_map: HashMap<String, HashSet<String>> = HashMap::new();
     // Map (*not* normalized) form -> lemma
     let mut lemma_map: HashMap<String, String> = HashMap::new();
     // Map lemma -> all associated forms
     let mut forms_map: HashMap<String, HashSet<String>> = HashMap::new();
+    // Map (*not* normalized) form -> metadata
+    let mut info_map: HashMap<String, WordInfo> = HashMap::new();
     words.iter().for_each(|(form, lemma)| {
         // Get the normalized form of this word for the accent map
-        let stripped = unidecode(form);
+        let stripped = language.normalize(form);
         accent_map.entry(stripped).or_default().insert(form.clone());
 
         // Map this form to its lemma
@@ -95,70 +276,492 @@ pub fn create_puzzle_from_letters(letters: Vec<char>) -> Result<Puzzle, Error> {
             .entry(lemma.clone())
             .or_default()
             .insert(form.clone());
+
+        if let Some(info) = dictionary.info(form, lemma) {
+            info_map.insert(form.clone(), info);
+        }
     });
 
-    Ok(Puzzle {
+    Puzzle {
         letters,
         words: accent_map,
         lemmas: lemma_map,
         forms: forms_map,
         pangrams,
         day: None,
-    })
+        number: None,
+        language,
+        rating: difficulty::rate(&info_map),
+        info: info_map,
+        centers: Vec::new(),
+        scoring: ScoringPreset::default(),
+        accents: AccentPolicy::default(),
+    }
 }
 
-pub fn daily_puzzle(day: u32) -> Result<Puzzle, Error> {
-    println!("Creating daily puzzle for day {}", day);
+/// Check that a puzzle has between `min_words` and `max_words` words
+fn check_bounds(puzzle: &Puzzle, min_words: usize, max_words: usize) -> Result<(), Error> {
+    let words = puzzle.word_count();
+    if words < min_words {
+        println!(
+            "Failed, only found {} words from {:?}",
+            words, puzzle.letters
+        );
+        return Err(Error::BadPuzzle {
+            reason: "Too few words".into(),
+            letters: puzzle.letters.clone(),
+            words,
+        });
+    }
+
+    if words > max_words {
+        println!(
+            "Failed, found too many words ({}) from {:?}",
+            words, puzzle.letters
+        );
+        return Err(Error::BadPuzzle {
+            reason: "Too many words".into(),
+            letters: puzzle.letters.clone(),
+            words,
+        });
+    }
+
+    Ok(())
+}
+
+/// Build and evaluate the puzzles of a set of letters with each of the letters
+/// as the center. The other letters keep their order.
+pub fn evaluate_centers(
+    letters: &[char],
+    options: &DailyOptions,
+) -> Result<Vec<(Puzzle, CenterEvaluation)>, Error> {
+    Ok(evaluate_centers_in(
+        &dictionary(options.language)?,
+        letters,
+        options,
+    ))
+}
+
+/// Evaluate the centers of a set of letters out of a word list, see
+/// [evaluate_centers]
+fn evaluate_centers_in(
+    dictionary: &words::Dictionary,
+    letters: &[char],
+    options: &DailyOptions,
+) -> Vec<(Puzzle, CenterEvaluation)> {
+    let letter_set: HashSet<char> = HashSet::from_iter(letters.iter().copied());
+
+    // Only words made of just these letters can be in any of the puzzles, so
+    // only search the whole dictionary once
+    let candidates: Vec<&(&str, &str)> = dictionary
+        .words
+        .iter()
+        .filter(|(form, _)| {
+            dictionary
+                .language
+                .normalize(form)
+                .chars()
+                .all(|c| letter_set.contains(&c))
+        })
+        .collect();
+
+    letters
+        .iter()
+        .map(|&center| {
+            let mut ordered = vec![center];
+            ordered.extend(letters.iter().filter(|&&c| c != center));
+            let puzzle = build_puzzle(dictionary, candidates.iter().copied(), ordered);
+            let rejection = check_bounds(&puzzle, options.min_words, options.max_words)
+                .err()
+                .map(|err| err.to_string());
+            let evaluation = quality::evaluate(&puzzle, options, rejection);
+            (puzzle, evaluation)
+        })
+        .collect()
+}
+
+pub(crate) fn dictionary(language: Language) -> Result<words::Dictionary, Error> {
+    words::dictionary(language).ok_or(Error::UnsupportedLanguage(language.to_string()))
+}
+
+/// Options for generating a daily puzzle
+#[derive(Clone, Copy, Debug)]
+pub struct DailyOptions {
+    pub language: Language,
+    /// Difficulty of the puzzle, or `None` for the normal daily puzzle
+    pub difficulty: Option<Difficulty>,
+    pub min_words: usize,
+    pub max_words: usize,
+    /// Number of letter sets to try before giving up
+    pub max_tries: usize,
+    /// How to choose the center out of a letter set
+    pub center_policy: CenterPolicy,
+    /// Days less than this many days apart do not share a letter set, unless
+    /// all of a day's scheduled letter sets fail. Zero to pick letter sets at
+    /// random instead.
+    pub no_repeat_window: usize,
+    /// How the accented variants of words are awarded
+    pub accents: AccentPolicy,
+}
+
+impl Default for DailyOptions {
+    fn default() -> Self {
+        DailyOptions {
+            language: Language::default(),
+            difficulty: None,
+            min_words: MIN_WORDS,
+            max_words: MAX_WORDS,
+            max_tries: 100,
+            center_policy: CenterPolicy::default(),
+            no_repeat_window: 30,
+            accents: AccentPolicy::default(),
+        }
+    }
+}
+
+pub fn daily_puzzle(day: u32, language: Language) -> Result<Puzzle, Error> {
+    daily_puzzle_with_options(
+        day,
+        DailyOptions {
+            language,
+            ..Default::default()
+        },
+    )
+}
+
+/// Create the day's puzzle. Each difficulty is generated from its own stream
+/// of the day's RNG, so the easy, normal, and hard puzzles of a day differ. For
+/// a difficulty, pangrams (and random centers) are chosen biased by how common
+/// they are, and puzzles are only accepted if they are rated as that
+/// difficulty, which needs a word list generated with metadata. From
+/// [CUTOVER_DAY], letter sets are taken from the day's [Rotation] so that
+/// nearby days do not repeat them, including the last days before it, falling
+/// back to random pangrams. Every letter of a letter set is considered as the
+/// center before trying another letter set, and the center is chosen by the
+/// options' policy. Normal puzzles of days before [CUTOVER_DAY] are generated
+/// as they always were instead.
+pub fn daily_puzzle_with_options(day: u32, options: DailyOptions) -> Result<Puzzle, Error> {
+    daily_puzzle_in(&dictionary(options.language)?, day, options)
+}
+
+/// Create the day's puzzle out of a word list, see [daily_puzzle_with_options]
+pub(crate) fn daily_puzzle_in(
+    dictionary: &words::Dictionary,
+    day: u32,
+    options: DailyOptions,
+) -> Result<Puzzle, Error> {
+    let DailyOptions {
+        language,
+        difficulty,
+        max_tries,
+        center_policy,
+        no_repeat_window,
+        ..
+    } = options;
+    println!("Creating daily {} puzzle for day {}", language, day);
+    if day < CUTOVER_DAY && difficulty.is_none() {
+        return legacy_daily_puzzle(dictionary, day, &options);
+    }
 
     // Create a random number generator seeded by days since the epoch
     let seed = day;
     let mut rng = ChaCha8Rng::seed_from_u64(seed.into());
+    rng.set_stream(difficulty.map_or(0, |difficulty| difficulty.stream()));
 
-    let all_pangrams = words::palabras::PANGRAMS;
+    let all_pangrams = dictionary.pangrams;
+    if difficulty.is_some() && !dictionary.has_metadata() {
+        return Err(Error::InvalidInput(format!(
+            "The {} word list has no metadata to rate puzzles by difficulty",
+            language
+        )));
+    }
+    let bias = difficulty.map(|difficulty| FrequencyBias::new(dictionary, difficulty));
+    let pangram_weight = |pangram: &str| bias.as_ref().map_or(1.0, |b| b.pangram_weight(pangram));
+    let rotation = Rotation::new(
+        dictionary,
+        no_repeat_window,
+        rng.get_stream(),
+        bias.as_ref()
+            .map(|_| &pangram_weight as &dyn Fn(&str) -> f32),
+    )
+    .preceded_by(CUTOVER_DAY, LEGACY_LETTER_SETS);
+    let mut scheduled = match day >= CUTOVER_DAY {
+        true => rotation.candidates(day),
+        false => Vec::new(),
+    }
+    .into_iter();
 
-    let mut pangram;
     let mut letters: Vec<char>;
 
     let mut tries = 0;
-    let mut puzzle;
+    let puzzle;
 
     loop {
         tries += 1;
 
-        // Choose a random pangram
-        let Some(chosen_pangram) = all_pangrams.choose(&mut rng) else {
-            return Err(Error::Message("No pangrams to choose from".into()));
-        };
-        pangram = chosen_pangram;
+        if let Some(letter_set) = scheduled.next() {
+            // Use the next of the day's scheduled letter sets, which are sorted
+            letters = letter_set.to_vec();
+            println!("Trying scheduled {:?}", letters);
+        } else {
+            // Choose a random pangram
+            let chosen_pangram = match &bias {
+                Some(_) => all_pangrams
+                    .choose_weighted(&mut rng, |pangram| pangram_weight(pangram))
+                    .ok(),
+                None => all_pangrams.choose(&mut rng),
+            };
+            let Some(pangram) = chosen_pangram else {
+                return Err(no_pangrams(language));
+            };
 
-        // Extract the unique letters from the pangram and shuffle to pick the
-        // center letter. Sort first so that the seedable RNG's determinism is
-        // not affect by the HashSet
-        let letter_set: HashSet<char> = HashSet::from_iter(unidecode(pangram).chars());
-        letters = letter_set.iter().copied().collect();
-        letters.sort();
+            // Extract the unique letters from the pangram. Sort them so that
+            // the seedable RNG's determinism is not affect by the HashSet
+            let letter_set: HashSet<char> = HashSet::from_iter(language.normalize(pangram).chars());
+            letters = letter_set.iter().copied().collect();
+            letters.sort();
+            println!("Trying {:?} from {}", letters, pangram);
+        }
         letters.shuffle(&mut rng);
 
-        println!("Trying {:?} from {}", letters, pangram);
+        // Try each letter as the center, and try again if none of them make a
+        // good puzzle
+        let mut candidates = evaluate_centers_in(dictionary, &letters, &options);
+        let evaluations: Vec<CenterEvaluation> = candidates
+            .iter()
+            .map(|(_, evaluation)| evaluation.clone())
+            .collect();
+        let chosen = quality::choose_center(center_policy, &evaluations, &mut rng, |center| {
+            bias.as_ref().map_or(1.0, |bias| bias.center_weight(center))
+        });
+        if let Some(chosen) = chosen {
+            let (mut chosen, evaluation) = candidates.swap_remove(chosen);
+            println!(
+                "Chose center {} ({:.2}) by {:?} out of {:?}",
+                evaluation.center, evaluation.quality, center_policy, evaluations
+            );
+            chosen.centers = evaluations;
+            puzzle = chosen;
+            break;
+        }
+        println!("Bad letters: no acceptable center");
+
+        if tries > max_tries {
+            return Err(Error::Exhausted { tries });
+        }
+    }
+    println!("Took {} tries to create a puzzle", tries);
+
+    Ok(puzzle.with_day(day).with_accents(options.accents))
+}
+
+/// Create the day's puzzle as before [CUTOVER_DAY]: the shuffled letters of
+/// random pangrams are tried, with the first letter as the center, until they
+/// make a puzzle
+fn legacy_daily_puzzle(
+    dictionary: &words::Dictionary,
+    day: u32,
+    options: &DailyOptions,
+) -> Result<Puzzle, Error> {
+    let language = dictionary.language;
+    let mut rng = ChaCha8Rng::seed_from_u64(day.into());
+    let all_pangrams = dictionary.pangrams;
+
+    let mut tries = 0;
+    loop {
+        tries += 1;
 
-        // Try to create the puzzle, and keep try again if these letters make a
-        // bad puzzle
-        puzzle = create_puzzle_from_letters(letters);
-        match puzzle {
-            Err(Error::BadPuzzle(message)) => println!("Bad puzzle: {}", message),
-            _ => break,
+        let Some(pangram) = all_pangrams.choose(&mut rng) else {
+            return Err(no_pangrams(language));
         };
+        // Sort the letters before shuffling so that the seedable RNG's
+        // determinism is not affected by the HashSet
+        let letter_set: HashSet<char> = HashSet::from_iter(language.normalize(pangram).chars());
+        let mut letters: Vec<char> = letter_set.iter().copied().collect();
+        letters.sort();
+        letters.shuffle(&mut rng);
+        println!("Trying {:?} from {}", letters, pangram);
 
-        if tries > 100 {
-            return Err(Error::Message(
-                "Too many tries, failed to find puzzle".into(),
-            ));
+        match create_puzzle_in(dictionary, letters, options.min_words, options.max_words) {
+            Ok(puzzle) => {
+                println!("Took {} tries to create a puzzle", tries);
+                return Ok(puzzle.with_day(day).with_accents(options.accents));
+            }
+            Err(Error::BadPuzzle { reason, .. }) => println!("Bad puzzle: {}", reason),
+            Err(err) => return Err(err),
+        }
+
+        if tries > options.max_tries {
+            return Err(Error::Exhausted { tries });
         }
     }
-    println!("Took {} tries to create a puzzle", tries);
+}
+
+/// The error of a word list without pangrams to make daily puzzles of
+fn no_pangrams(language: Language) -> Error {
+    Error::InvalidInput(format!(
+        "The {} word list has no pangrams to choose from",
+        language
+    ))
+}
+
+/// Weights for choosing pangrams and center letters for a difficulty, from the
+/// word list's metadata
+struct FrequencyBias {
+    difficulty: Difficulty,
+    /// Map normalized pangram -> difficulty of its easiest form
+    pangrams: HashMap<String, f32>,
+    /// Map letter -> share of the total frequency of words containing it
+    letters: HashMap<char, f32>,
+}
+
+impl FrequencyBias {
+    fn new(dictionary: &words::Dictionary, difficulty: Difficulty) -> FrequencyBias {
+        let pangram_set: HashSet<&str> = dictionary.pangrams.iter().copied().collect();
+        let mut pangrams: HashMap<String, f32> = HashMap::new();
+        let mut letters: HashMap<char, f32> = HashMap::new();
+        let mut total = 0.0;
+        dictionary.words.iter().zip(dictionary.metadata).for_each(
+            |((form, _), (_, frequency, _, word_difficulty))| {
+                let normalized = dictionary.language.normalize(form);
+                let unique: HashSet<char> = normalized.chars().collect();
+                unique.iter().for_each(|c| {
+                    *letters.entry(*c).or_default() += frequency;
+                });
+                total += frequency;
+
+                if pangram_set.contains(normalized.as_str()) {
+                    let easiest = pangrams.entry(normalized).or_insert(*word_difficulty);
+                    *easiest = easiest.min(*word_difficulty);
+                }
+            },
+        );
+        letters.values_mut().for_each(|share| *share /= total);
+
+        FrequencyBias {
+            difficulty,
+            pangrams,
+            letters,
+        }
+    }
+
+    fn pangram_weight(&self, pangram: &str) -> f32 {
+        let difficulty = self.pangrams.get(pangram).copied().unwrap_or(0.5);
+        self.weight(1.0 - difficulty)
+    }
 
-    puzzle.and_then(|mut puzzle| {
-        puzzle.day = Some(day);
-        Ok(puzzle)
-    })
+    fn center_weight(&self, letter: char) -> f32 {
+        self.weight(self.letters.get(&letter).copied().unwrap_or(0.0))
+    }
+
+    /// Weight of something from how common it is, from 0 to 1: easy puzzles
+    /// prefer common things, hard puzzles rare things, and normal puzzles
+    /// things in between
+    fn weight(&self, commonness: f32) -> f32 {
+        const MIN_WEIGHT: f32 = 0.01;
+        let weight = match self.difficulty {
+            Difficulty::Easy => commonness * commonness,
+            Difficulty::Normal => 1.0 - (commonness - 0.5).abs(),
+            Difficulty::Hard => (1.0 - commonness) * (1.0 - commonness),
+        };
+        weight.max(MIN_WEIGHT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+    use words::Categoria;
+
+    #[test]
+    fn days_before_the_cutover_keep_their_puzzles() {
+        [
+            (19_800, "dsegiou"),
+            (20_000, "uetqdni"),
+            (20_250, "ifundot"),
+            (20_500, "gitneva"),
+            (20_743, "iulnyce"),
+        ]
+        .into_iter()
+        .for_each(|(day, letters)| {
+            let puzzle = daily_puzzle(day, Language::Es).unwrap();
+            assert_eq!(puzzle.letters().iter().collect::<String>(), letters);
+            assert_eq!(puzzle.day(), Some(day));
+        });
+    }
+
+    #[test]
+    fn legacy_letter_sets_are_the_last_days_before_the_cutover() {
+        let days = CUTOVER_DAY - LEGACY_LETTER_SETS.len() as u32..CUTOVER_DAY;
+        days.zip(LEGACY_LETTER_SETS).for_each(|(day, letter_set)| {
+            let mut letters = daily_puzzle(day, Language::Es).unwrap().letters().to_vec();
+            letters.sort();
+            assert_eq!(letters.into_iter().collect::<String>(), *letter_set);
+        });
+    }
+
+    #[test]
+    fn words_have_their_metadata() {
+        let letters = "abcdefg".chars().collect();
+        let puzzle = create_puzzle_in(&testing::dictionary(), letters, 0, usize::MAX).unwrap();
+        let info = puzzle.info("bacdefg").unwrap();
+        assert_eq!(info.category, Categoria::V);
+        assert_eq!(info.frequency, 80.0);
+        assert_eq!(puzzle.lemma_info("bacdefg"), puzzle.info("abcdefg"));
+    }
+
+    #[test]
+    fn difficulties_make_their_own_puzzles() {
+        let dictionary = testing::dictionary();
+        let letters = |difficulty| {
+            let options = DailyOptions {
+                difficulty: Some(difficulty),
+                min_words: 1,
+                max_words: 10,
+                ..Default::default()
+            };
+            let puzzle = daily_puzzle_in(&dictionary, CUTOVER_DAY, options).unwrap();
+            assert_eq!(puzzle.rating().unwrap().difficulty, difficulty);
+            let mut letters = puzzle.letters().to_vec();
+            letters.sort();
+            letters.into_iter().collect::<String>()
+        };
+
+        assert_eq!(letters(Difficulty::Easy), "abcdefg");
+        assert_eq!(letters(Difficulty::Normal), "hijklmn");
+        assert_eq!(letters(Difficulty::Hard), "opqrstu");
+        // The same day gives the same puzzles
+        [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard]
+            .into_iter()
+            .for_each(|difficulty| assert_eq!(letters(difficulty), letters(difficulty)));
+    }
+
+    #[test]
+    fn word_lists_need_pangrams() {
+        let dictionary = words::Dictionary {
+            pangrams: &[],
+            ..testing::dictionary()
+        };
+        [CUTOVER_DAY - 1, CUTOVER_DAY].into_iter().for_each(|day| {
+            let puzzle = daily_puzzle_in(&dictionary, day, DailyOptions::default());
+            assert!(
+                matches!(puzzle, Err(Error::InvalidInput(_))),
+                "{:?}",
+                puzzle.map(|p| p.day())
+            );
+        });
+    }
+
+    #[test]
+    fn difficulties_need_metadata() {
+        let options = DailyOptions {
+            difficulty: Some(Difficulty::Easy),
+            ..Default::default()
+        };
+        assert!(matches!(
+            daily_puzzle_with_options(CUTOVER_DAY, options),
+            Err(Error::InvalidInput(_))
+        ));
+    }
 }
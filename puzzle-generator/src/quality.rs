@@ -0,0 +1,114 @@
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+
+use crate::puzzles::{DailyOptions, Puzzle};
+
+/// How to choose the center letter out of a letter set
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CenterPolicy {
+    /// The acceptable center with the highest quality
+    #[default]
+    Best,
+    /// Any acceptable center, at random
+    RandomAcceptable,
+    /// The best acceptable consonant, or the best acceptable vowel if no
+    /// consonant is acceptable
+    AvoidVowels,
+}
+
+/// How a letter does as the center of a letter set
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CenterEvaluation {
+    pub center: char,
+    pub words: usize,
+    pub pangrams: usize,
+    /// How good the puzzle is, from 0 to 1
+    pub quality: f32,
+    /// Why the puzzle is not acceptable, if it isn't
+    pub rejection: Option<String>,
+}
+
+impl CenterEvaluation {
+    pub fn acceptable(&self) -> bool {
+        self.rejection.is_none()
+    }
+}
+
+/// Evaluate a puzzle, which the caller may have already found unacceptable.
+/// Good puzzles have a number of words in the middle of the options' bounds,
+/// only a few pangrams, and the options' difficulty.
+pub fn evaluate(
+    puzzle: &Puzzle,
+    options: &DailyOptions,
+    mut rejection: Option<String>,
+) -> CenterEvaluation {
+    let words = puzzle.word_count();
+    let pangrams = puzzle.pangrams().len();
+
+    let middle = (options.min_words + options.max_words) as f32 / 2.0;
+    let spread = ((options.max_words - options.min_words) as f32 / 2.0).max(1.0);
+    let words_quality = (1.0 - (words as f32 - middle).abs() / spread).clamp(0.0, 1.0);
+
+    // There is always at least one pangram, but many are too easy to spot
+    let pangrams_quality = match pangrams {
+        0 => 0.0,
+        1..=3 => 1.0,
+        _ => 3.0 / pangrams as f32,
+    };
+
+    let difficulty_quality = match (options.difficulty, puzzle.rating()) {
+        (Some(difficulty), Some(rating)) if rating.difficulty != difficulty => {
+            rejection.get_or_insert(format!("{:?} not {:?}", rating.difficulty, difficulty));
+            0.0
+        }
+        (Some(_), None) => {
+            rejection.get_or_insert("Unrated".into());
+            0.0
+        }
+        _ => 1.0,
+    };
+
+    CenterEvaluation {
+        center: puzzle.letters()[0],
+        words,
+        pangrams,
+        quality: 0.5 * words_quality + 0.3 * pangrams_quality + 0.2 * difficulty_quality,
+        rejection,
+    }
+}
+
+/// Choose the index of a center by the policy, `None` if no center is
+/// acceptable. Random choices are weighted by `weight`.
+pub fn choose_center<R: Rng>(
+    policy: CenterPolicy,
+    evaluations: &[CenterEvaluation],
+    rng: &mut R,
+    weight: impl Fn(char) -> f32,
+) -> Option<usize> {
+    let acceptable: Vec<usize> = (0..evaluations.len())
+        .filter(|&i| evaluations[i].acceptable())
+        .collect();
+    let best = |indices: &[usize]| {
+        indices
+            .iter()
+            .copied()
+            .max_by(|&a, &b| evaluations[a].quality.total_cmp(&evaluations[b].quality))
+    };
+
+    match policy {
+        CenterPolicy::Best => best(&acceptable),
+        CenterPolicy::RandomAcceptable => acceptable
+            .choose_weighted(rng, |&i| weight(evaluations[i].center))
+            .ok()
+            .copied(),
+        CenterPolicy::AvoidVowels => {
+            let consonants: Vec<usize> = acceptable
+                .iter()
+                .copied()
+                .filter(|&i| !"aeiou".contains(evaluations[i].center))
+                .collect();
+            best(&consonants).or_else(|| best(&acceptable))
+        }
+    }
+}
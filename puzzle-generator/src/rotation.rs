@@ -0,0 +1,207 @@
+use rand::{seq::SliceRandom, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::collections::{HashMap, HashSet};
+
+/// RNG streams at and above this are used for rotations, so they never
+/// coincide with the streams of the daily puzzles
+const ROTATION_STREAM: u64 = 1 << 32;
+
+/// A stateless schedule of the pangrams' letter sets. Days are split into
+/// cycles as long as the number of letter sets, and each cycle goes through
+/// every letter set in a seeded order, so a letter set is only repeated once
+/// per cycle. The first days of a cycle are kept from using the letter sets of
+/// the last days of the previous cycle, so no two days less than `window` days
+/// apart are scheduled the same letter set.
+pub struct Rotation {
+    /// Unique letter sets, each sorted, in sorted order
+    sets: Vec<Vec<char>>,
+    /// Weight of each letter set, earlier in a cycle the heavier it is
+    weights: Option<Vec<f32>>,
+    window: usize,
+    stream: u64,
+    /// A day, and the letter sets played on the days before it that the
+    /// window of days from it avoids
+    preceding: Option<(u32, HashSet<usize>)>,
+}
+
+impl Rotation {
+    /// Create the rotation of the dictionary's pangrams' letter sets. Each RNG
+    /// stream has its own rotation, and the letter sets can be weighted to come
+    /// earlier in the cycle by the weight of their heaviest pangram.
+    pub fn new(
+        dictionary: &words::Dictionary,
+        window: usize,
+        stream: u64,
+        pangram_weight: Option<&dyn Fn(&str) -> f32>,
+    ) -> Rotation {
+        let language = dictionary.language;
+        let mut by_letters: HashMap<Vec<char>, f32> = HashMap::new();
+        dictionary.pangrams.iter().for_each(|pangram| {
+            let mut letters: Vec<char> = language
+                .normalize(pangram)
+                .chars()
+                .collect::<HashSet<char>>()
+                .into_iter()
+                .collect();
+            letters.sort();
+            let weight = pangram_weight.map_or(1.0, |weight| weight(pangram));
+            let heaviest = by_letters.entry(letters).or_insert(weight);
+            *heaviest = heaviest.max(weight);
+        });
+        let mut sets: Vec<(Vec<char>, f32)> = by_letters.into_iter().collect();
+        sets.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let weights = pangram_weight.map(|_| sets.iter().map(|(_, weight)| *weight).collect());
+        let sets: Vec<Vec<char>> = sets.into_iter().map(|(letters, _)| letters).collect();
+        // The start of a cycle swaps letter sets with its middle, which needs
+        // to be big enough
+        let window = window.min(sets.len() / 4);
+
+        Rotation {
+            sets,
+            weights,
+            window,
+            stream,
+            preceding: None,
+        }
+    }
+
+    /// The rotation, with the `window` days from `start` not using the letter
+    /// sets played before it, e.g. on the days before the rotation was first
+    /// used. Letter sets that are not the sorted letters of one of the
+    /// pangrams are ignored.
+    pub fn preceded_by(mut self, start: u32, letter_sets: &[&str]) -> Rotation {
+        let preceding = letter_sets
+            .iter()
+            .filter_map(|letters| {
+                let letters: Vec<char> = letters.chars().collect();
+                self.sets.binary_search(&letters).ok()
+            })
+            .collect();
+        self.preceding = Some((start, preceding));
+        self
+    }
+
+    /// The letter sets to try for a day, in order. The day's scheduled letter
+    /// set comes first, followed by the letter sets scheduled `window`,
+    /// `2 * window`, etc. days later in the same cycle, so that a day falling
+    /// back to another letter set does not repeat a nearby day's. Empty if the
+    /// window is zero. Days in the window after the rotation's preceding
+    /// letter sets skip them.
+    pub fn candidates(&self, day: u32) -> Vec<&[char]> {
+        if self.window == 0 || self.sets.is_empty() {
+            return Vec::new();
+        }
+
+        let length = self.sets.len() as u64;
+        let (cycle, position) = (day as u64 / length, (day as u64 % length) as usize);
+        let (order, previous_end) = self.cycle(cycle);
+        let preceding = self
+            .preceding
+            .as_ref()
+            .filter(|(start, _)| (*start..start + self.window as u32).contains(&day))
+            .map(|(_, preceding)| preceding);
+        order[position..]
+            .iter()
+            .step_by(self.window)
+            // The start of the cycle must also avoid the end of the previous
+            // cycle when falling back
+            .filter(|i| position >= self.window || !previous_end.contains(i))
+            .filter(|i| preceding.is_none_or(|preceding| !preceding.contains(i)))
+            .map(|&i| self.sets[i].as_slice())
+            .collect()
+    }
+
+    /// The order of the letter sets of a cycle, with its start not using the
+    /// letter sets at the end of the previous cycle, and those letter sets
+    fn cycle(&self, cycle: u64) -> (Vec<usize>, HashSet<usize>) {
+        let mut order = self.shuffled(cycle);
+        if cycle == 0 {
+            return (order, HashSet::new());
+        }
+
+        let previous = self.shuffled(cycle - 1);
+        let previous_end: HashSet<usize> = previous[previous.len() - self.window..]
+            .iter()
+            .copied()
+            .collect();
+        // Swap with the middle of the cycle so that the end of the cycle is
+        // left as it was shuffled for the next cycle to avoid
+        let middle = self.window..order.len() - self.window;
+        let replacements: Vec<usize> = middle
+            .filter(|&j| !previous_end.contains(&order[j]))
+            .collect();
+        let mut replacements = replacements.into_iter();
+        for i in 0..self.window {
+            if previous_end.contains(&order[i]) {
+                if let Some(j) = replacements.next() {
+                    order.swap(i, j);
+                }
+            }
+        }
+
+        (order, previous_end)
+    }
+
+    /// Seeded order of the letter sets of a cycle, before avoiding the previous
+    /// cycle
+    fn shuffled(&self, cycle: u64) -> Vec<usize> {
+        let mut rng = ChaCha8Rng::seed_from_u64(cycle);
+        rng.set_stream(ROTATION_STREAM + self.stream);
+
+        let mut order: Vec<usize> = (0..self.sets.len()).collect();
+        match &self.weights {
+            // Weighted random order, where each letter set is keyed by
+            // u^(1 / weight) and heavier keys come first
+            Some(weights) => {
+                let mut keys: Vec<(f32, usize)> = order
+                    .iter()
+                    .map(|&i| {
+                        let u: f32 = rng.gen_range(f32::EPSILON..1.0);
+                        (u.powf(1.0 / weights[i].max(f32::EPSILON)), i)
+                    })
+                    .collect();
+                keys.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+                order = keys.into_iter().map(|(_, i)| i).collect();
+            }
+            None => order.shuffle(&mut rng),
+        }
+        order
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::puzzles::{CUTOVER_DAY, LEGACY_LETTER_SETS};
+    use words::Language;
+
+    #[test]
+    fn nearby_days_do_not_repeat_letter_sets() {
+        let dictionary = words::dictionary(Language::Es).unwrap();
+        let window = 30;
+        let rotation = Rotation::new(&dictionary, window, 0, None)
+            .preceded_by(CUTOVER_DAY, LEGACY_LETTER_SETS);
+        // The last days before the cutover, then two whole cycles from it,
+        // across the start of a cycle
+        let mut played: Vec<Vec<char>> = LEGACY_LETTER_SETS
+            .iter()
+            .map(|letters| letters.chars().collect())
+            .collect();
+        (CUTOVER_DAY..CUTOVER_DAY + 2 * rotation.sets.len() as u32).for_each(|day| {
+            let nearby = &played[played.len() + 1 - window..];
+            let candidates = rotation.candidates(day);
+            // None of the letter sets the day could fall back to were played
+            // on a nearby day either
+            candidates.iter().for_each(|letters| {
+                assert!(
+                    !nearby.iter().any(|played| played == letters),
+                    "{:?} repeated on day {}",
+                    letters,
+                    day
+                );
+            });
+            played.push(candidates[0].to_vec());
+        });
+    }
+}
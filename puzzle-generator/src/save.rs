@@ -0,0 +1,232 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use words::Language;
+
+use crate::{
+    accents::AccentPolicy,
+    hints::Hint,
+    puzzles::{self, Puzzle},
+    scoring::ScoringPreset,
+    session::GameSession,
+    Error,
+};
+
+/// Format of the save states written by this version
+pub const SAVE_STATE_FORMAT: u32 = 1;
+
+/// The progress of a daily game, without the puzzle's words: the puzzle is
+/// rebuilt from its day and letters when the game is loaded
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SaveState {
+    pub format: u32,
+    pub day: u32,
+    pub language: Language,
+    /// The letters, center first
+    pub letters: String,
+    /// Version of the word list the words were found with
+    pub version: String,
+    #[serde(default)]
+    pub scoring: ScoringPreset,
+    #[serde(default)]
+    pub accents: AccentPolicy,
+    /// The normalized words found, most recent first. All of their variants
+    /// that can be found were found, except those in `forms`.
+    pub found: Vec<String>,
+    /// Found forms whose other accent variants were not found, with strict
+    /// accents or [AccentPolicy::RequireAccent]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub forms: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hints: Vec<Hint>,
+    #[serde(default)]
+    pub deduct_hint_costs: bool,
+    #[serde(default)]
+    pub strict_accents: bool,
+    #[serde(default)]
+    pub bonus: u32,
+    /// When the game was started and last saved, in milliseconds since the
+    /// Unix epoch
+    pub started: u64,
+    pub updated: u64,
+}
+
+/// The fields of a puzzle saved by the frontend under `${day}-puzzle`, before
+/// save states, needed to migrate it
+#[derive(Deserialize)]
+struct LegacyPuzzle {
+    letters: Vec<String>,
+    day: u32,
+    #[serde(default)]
+    found: Vec<String>,
+}
+
+impl SaveState {
+    /// Save a daily game
+    pub fn from_session(
+        session: &GameSession,
+        started: u64,
+        updated: u64,
+    ) -> Result<SaveState, Error> {
+        let puzzle = &session.puzzle;
+        let Some(day) = puzzle.day() else {
+            return Err(Error::InvalidInput(
+                "Only daily puzzles can be saved".into(),
+            ));
+        };
+        let language = puzzle.language();
+        let findable: HashSet<&String> = session.findable().into_iter().collect();
+
+        let mut found: Vec<String> = Vec::new();
+        let mut forms: Vec<String> = Vec::new();
+        session.found.iter().for_each(|word| {
+            let normalized = language.normalize(word);
+            let all_found = puzzle.forms_of_word(&normalized).is_some_and(|variants| {
+                variants
+                    .iter()
+                    .filter(|variant| findable.contains(variant))
+                    .all(|variant| session.found.contains(variant))
+            });
+            if !all_found {
+                forms.push(word.clone());
+            } else if !found.contains(&normalized) {
+                found.push(normalized);
+            }
+        });
+
+        Ok(SaveState {
+            format: SAVE_STATE_FORMAT,
+            day,
+            language,
+            letters: puzzle.letters().iter().collect(),
+            version: puzzles::dictionary(language)?.version.to_string(),
+            scoring: puzzle.scoring(),
+            accents: puzzle.accents(),
+            found,
+            forms,
+            hints: session.hints.clone(),
+            deduct_hint_costs: session.deduct_hint_costs,
+            strict_accents: session.strict_accents,
+            bonus: session.bonus,
+            started,
+            updated,
+        })
+    }
+
+    /// Whether the game was saved with the current word list
+    pub fn is_current(&self) -> Result<bool, Error> {
+        Ok(self.version == puzzles::dictionary(self.language)?.version)
+    }
+
+    /// Rebuild the game. If the word list has changed since the game was
+    /// saved, the found words that are no longer in the puzzle are dropped,
+    /// and returned with the game.
+    pub fn to_session(&self) -> Result<(GameSession, Vec<String>), Error> {
+        let letters: Vec<char> = self.letters.chars().collect();
+        let puzzle = puzzles::create_puzzle_with_bounds(self.language, letters, 0, usize::MAX)?
+            .with_day(self.day)
+            .with_scoring(self.scoring)
+            .with_accents(self.accents);
+
+        let mut session = GameSession::new(puzzle);
+        session.hints = self.hints.clone();
+        session.deduct_hint_costs = self.deduct_hint_costs;
+        session.strict_accents = self.strict_accents;
+        session.bonus = self.bonus;
+
+        let findable: HashSet<String> = session.findable().into_iter().cloned().collect();
+        let mut found: Vec<String> = Vec::new();
+        let mut dropped: Vec<String> = Vec::new();
+        self.found
+            .iter()
+            .for_each(|word| match session.puzzle.forms_of_word(word) {
+                Some(variants) => {
+                    let mut variants: Vec<&String> = variants
+                        .iter()
+                        .filter(|variant| findable.contains(*variant))
+                        .collect();
+                    variants.sort();
+                    found.extend(variants.into_iter().cloned());
+                }
+                None => dropped.push(word.clone()),
+            });
+        self.forms
+            .iter()
+            .for_each(|form| match session.puzzle.lemma(form) {
+                Some(_) => found.push(form.clone()),
+                None => dropped.push(form.clone()),
+            });
+        session.found = found;
+        Ok((session, dropped))
+    }
+
+    /// Rebuild the game and, if it was saved with another word list, the save
+    /// state with the current one. Returns the game, the save state, and the
+    /// found words dropped because they are no longer in the puzzle.
+    pub fn restore(self) -> Result<(GameSession, SaveState, Vec<String>), Error> {
+        let (session, dropped) = self.to_session()?;
+        if self.is_current()? {
+            return Ok((session, self, dropped));
+        }
+        let state = SaveState::from_session(&session, self.started, self.updated)?;
+        Ok((session, state, dropped))
+    }
+
+    /// Parse a save state, or migrate a puzzle saved under `${day}-puzzle`
+    /// before save states, which is dated `now`
+    pub fn load(json: &str, now: u64) -> Result<SaveState, Error> {
+        let value: serde_json::Value = serde_json::from_str(json)?;
+        let Some(format) = value.get("format") else {
+            return SaveState::from_legacy(serde_json::from_value(value)?, now);
+        };
+        if format.as_u64() != Some(SAVE_STATE_FORMAT.into()) {
+            return Err(Error::InvalidInput(format!(
+                "Unsupported save state format {}",
+                format
+            )));
+        }
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Migrate a legacy puzzle, which was always Spanish and awarded all of
+    /// the variants of a word
+    fn from_legacy(legacy: LegacyPuzzle, now: u64) -> Result<SaveState, Error> {
+        let letters: Vec<char> = legacy
+            .letters
+            .iter()
+            .flat_map(|letter| letter.to_lowercase().chars().collect::<Vec<char>>())
+            .collect();
+        let puzzle: Puzzle =
+            puzzles::create_puzzle_with_bounds(Language::Es, letters, 0, usize::MAX)?
+                .with_day(legacy.day);
+        let mut session = GameSession::new(puzzle);
+        session.found = legacy
+            .found
+            .into_iter()
+            .filter(|word| session.puzzle.lemma(word).is_some())
+            .collect();
+        SaveState::from_session(&session, now, now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    #[test]
+    fn restore_with_another_word_list() {
+        let mut session = GameSession::new(testing::puzzle().with_day(19_800));
+        session.found = vec!["dedos".into()];
+        let mut state = SaveState::from_session(&session, 1, 2).unwrap();
+        assert!(state.is_current().unwrap());
+
+        // A word the old word list had, but the current one does not
+        state.version = "es-general-00000000".into();
+        state.found.push("dudosa".into());
+        let (session, state, dropped) = state.restore().unwrap();
+        assert_eq!(session.found, ["dedos"]);
+        assert_eq!(dropped, ["dudosa"]);
+        assert!(state.is_current().unwrap());
+        assert_eq!(state.found, ["dedos"]);
+    }
+}
@@ -0,0 +1,106 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use words::Language;
+
+use crate::{
+    custom,
+    puzzles::{self, DailyOptions, Puzzle},
+    Error,
+};
+
+/// A scheduled day's puzzle, enough to build it without generating it. Entries
+/// can be edited by hand to curate a day, or to veto its generated letters.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ScheduleEntry {
+    pub day: u32,
+    pub language: Language,
+    /// The letters, center first
+    pub letters: String,
+    pub center: char,
+    /// Version of the word list the entry was scheduled with
+    pub version: String,
+}
+
+impl ScheduleEntry {
+    pub fn from_puzzle(day: u32, puzzle: &Puzzle, version: String) -> ScheduleEntry {
+        ScheduleEntry {
+            day,
+            language: puzzle.language(),
+            letters: puzzle.letters().iter().collect(),
+            center: puzzle.letters()[0],
+            version,
+        }
+    }
+}
+
+/// Generate the daily puzzles from day `from` to day `to`, inclusive. Days that
+/// already have an entry in `existing` keep it, so that regenerating a
+/// schedule does not undo the edits to it.
+pub fn schedule(
+    from: u32,
+    to: u32,
+    options: DailyOptions,
+    existing: Vec<ScheduleEntry>,
+) -> Result<Vec<ScheduleEntry>, Error> {
+    let version = puzzles::dictionary(options.language)?.version;
+    let mut entries: BTreeMap<u32, ScheduleEntry> = existing
+        .into_iter()
+        .map(|entry| (entry.day, entry))
+        .collect();
+
+    for day in from..=to {
+        if entries.contains_key(&day) {
+            println!("Keeping the scheduled puzzle for day {}", day);
+            continue;
+        }
+        let puzzle = puzzles::daily_puzzle_with_options(day, options)?;
+        entries.insert(
+            day,
+            ScheduleEntry::from_puzzle(day, &puzzle, version.to_string()),
+        );
+    }
+
+    Ok(entries.into_values().collect())
+}
+
+/// Build the puzzle of a schedule entry. The entry must have been scheduled
+/// with the current word list, since its letters were only checked against
+/// that one. Hand-curated puzzles are not held to the bounds on their number
+/// of words.
+pub fn puzzle_from_entry(entry: &ScheduleEntry) -> Result<Puzzle, Error> {
+    let version = puzzles::dictionary(entry.language)?.version;
+    if entry.version != version {
+        return Err(Error::VersionMismatch {
+            expected: version.to_string(),
+            found: entry.version.clone(),
+        });
+    }
+
+    let letters =
+        custom::puzzle_letters(entry.language, &entry.letters, &entry.center.to_string())?;
+    let puzzle = puzzles::create_puzzle_with_bounds(entry.language, letters, 0, usize::MAX)?;
+    Ok(puzzle.with_day(entry.day))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stale_entries_are_rejected() {
+        let mut entry = ScheduleEntry {
+            day: 19_800,
+            language: Language::Es,
+            letters: "dsegiou".into(),
+            center: 'd',
+            version: puzzles::dictionary(Language::Es).unwrap().version.into(),
+        };
+        assert_eq!(puzzle_from_entry(&entry).unwrap().day(), Some(19_800));
+
+        entry.version = "es-general-00000000".into();
+        assert!(matches!(
+            puzzle_from_entry(&entry),
+            Err(Error::VersionMismatch { found, .. }) if found == "es-general-00000000"
+        ));
+    }
+}
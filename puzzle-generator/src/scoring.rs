@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+
+/// Extra points for a pangram
+pub const PANGRAM_BONUS: u32 = 7;
+
+/// How words are scored
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScoringPreset {
+    /// 4-letter words are worth 1 point and longer words a point per letter,
+    /// with a bonus for pangrams
+    #[default]
+    Standard,
+    /// Every word is worth 1 point, with a bonus for pangrams
+    Flat,
+}
+
+impl ScoringPreset {
+    pub const ALL: [ScoringPreset; 2] = [ScoringPreset::Standard, ScoringPreset::Flat];
+
+    /// Score of a (*not* normalized) word
+    pub fn score(&self, word: &str, pangram: bool) -> u32 {
+        let bonus = if pangram { PANGRAM_BONUS } else { 0 };
+        match self {
+            ScoringPreset::Standard => match word.chars().count() as u32 {
+                4 => 1,
+                length => length + bonus,
+            },
+            ScoringPreset::Flat => 1 + bonus,
+        }
+    }
+}
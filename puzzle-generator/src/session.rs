@@ -0,0 +1,408 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+use crate::{
+    accents, days,
+    hints::{self, Hint, HintLevel},
+    puzzles::Puzzle,
+    submission::Rejection,
+};
+
+/// Fraction of the maximum score needed for each rank
+pub const SCORE_RANKS: [f32; 10] = [0.0, 0.02, 0.05, 0.08, 0.15, 0.25, 0.4, 0.5, 0.7, 1.0];
+
+/// Language of the game's interface
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    Es,
+    En,
+}
+
+impl Locale {
+    /// Pick the text of this locale
+    pub fn text<'a>(&self, es: &'a str, en: &'a str) -> &'a str {
+        match self {
+            Locale::Es => es,
+            Locale::En => en,
+        }
+    }
+
+    /// Name of a rank, an index into [SCORE_RANKS]
+    pub fn rank_name(&self, rank: usize) -> &'static str {
+        let names = match self {
+            Locale::Es => [
+                "Novato",
+                "Buen comienzo",
+                "Subiendo",
+                "Que bien",
+                "Macizo",
+                "Súper",
+                "Genial",
+                "Increíble",
+                "Genio",
+                "Abeja Reina",
+            ],
+            Locale::En => [
+                "Beginner",
+                "Good Start",
+                "Moving Up",
+                "Good",
+                "Solid",
+                "Nice",
+                "Great",
+                "Amazing",
+                "Genius",
+                "Queen Bee",
+            ],
+        };
+        names[rank.min(names.len() - 1)]
+    }
+}
+
+/// A player's game of a puzzle
+#[derive(Clone, Deserialize, Serialize)]
+pub struct GameSession {
+    pub puzzle: Puzzle,
+    /// The (*not* normalized) words found, most recent first
+    #[serde(default)]
+    pub found: Vec<String>,
+    /// Hints given, in order
+    #[serde(default)]
+    pub hints: Vec<Hint>,
+    /// Whether the costs of the hints are deducted from the score
+    #[serde(default)]
+    pub deduct_hint_costs: bool,
+    /// Submitted words that were not in the list, in order
+    #[serde(default)]
+    pub rejections: Vec<Rejection>,
+    /// Whether each accented variant must be typed with its exact accents,
+    /// for learners practicing them, whatever the puzzle's accent policy.
+    /// Correct accents earn [ACCENT_BONUS](crate::accents::ACCENT_BONUS)
+    /// points.
+    #[serde(default)]
+    pub strict_accents: bool,
+    /// Points for typing accents, with
+    /// [AccentBonus](crate::accents::AccentPolicy::AccentBonus) or strict
+    /// accents
+    #[serde(default)]
+    pub bonus: u32,
+}
+
+impl GameSession {
+    pub fn new(puzzle: Puzzle) -> GameSession {
+        GameSession {
+            puzzle,
+            found: Vec::new(),
+            hints: Vec::new(),
+            deduct_hint_costs: false,
+            rejections: Vec::new(),
+            strict_accents: false,
+            bonus: 0,
+        }
+    }
+
+    /// Score of the words found and their accent bonus, minus the costs of
+    /// the hints if they are deducted
+    pub fn score(&self) -> u32 {
+        let score: u32 = self.found.iter().map(|word| self.puzzle.score(word)).sum();
+        let score = score + self.bonus;
+        match self.deduct_hint_costs {
+            true => score.saturating_sub(self.hint_cost()),
+            false => score,
+        }
+    }
+
+    /// The score of finding all of the words that can be found
+    pub fn max_score(&self) -> u32 {
+        match self.strict_accents {
+            true => accents::strict_max_score(&self.puzzle),
+            false => self.puzzle.max_score(),
+        }
+    }
+
+    /// The words that can be found, sorted
+    pub fn findable(&self) -> Vec<&String> {
+        match self.strict_accents {
+            true => {
+                let mut words: Vec<&String> = self.puzzle.all_words().collect();
+                words.sort();
+                words
+            }
+            false => self.puzzle.accents().findable(&self.puzzle),
+        }
+    }
+
+    /// Total cost of the hints given
+    pub fn hint_cost(&self) -> u32 {
+        self.hints.iter().map(|hint| hint.cost).sum()
+    }
+
+    /// Give the next hint: a stronger clue about the word of the last hint if
+    /// it has not been found yet, otherwise the weakest clue about the easiest
+    /// unfound word. `None` if there is nothing left to give a clue about.
+    pub fn hint(&mut self) -> Option<&Hint> {
+        let language = self.puzzle.language();
+        let found: HashSet<String> = self
+            .found
+            .iter()
+            .map(|word| language.normalize(word))
+            .collect();
+        // With strict accents, the other variants of a found word are unfound
+        let unfound = |word: &String| match self.strict_accents {
+            true => !self.found.contains(word),
+            false => !found.contains(&language.normalize(word)),
+        };
+
+        let last = self
+            .hints
+            .last()
+            .map(|hint| hint.word.clone())
+            .filter(|word| unfound(word));
+        // Easiest first, by difficulty if the word list has metadata, then
+        // by length
+        let mut words: Vec<&String> = self.findable().into_iter().filter(|w| unfound(w)).collect();
+        words.sort_by(|a, b| {
+            let difficulty = |word: &str| self.puzzle.info(word).map_or(0.0, |i| i.difficulty);
+            difficulty(a)
+                .total_cmp(&difficulty(b))
+                .then(a.chars().count().cmp(&b.chars().count()))
+                .then(a.cmp(b))
+        });
+
+        let hint = last.iter().chain(words).find_map(|word| {
+            let given: HashSet<HintLevel> = self
+                .hints
+                .iter()
+                .filter(|hint| &hint.word == word)
+                .map(|hint| hint.clue.level())
+                .collect();
+            HintLevel::ALL
+                .iter()
+                .filter(|level| !given.contains(level))
+                .find_map(|&level| hints::clue(&self.puzzle, word, level))
+                .map(|clue| Hint {
+                    word: word.clone(),
+                    cost: clue.level().cost(),
+                    clue,
+                })
+        })?;
+        self.hints.push(hint);
+        self.hints.last()
+    }
+
+    /// Index of the rank reached into [SCORE_RANKS]
+    pub fn rank(&self) -> usize {
+        let (score, max_score) = (self.score(), self.max_score());
+        SCORE_RANKS
+            .iter()
+            .rposition(|fraction| score as f32 >= (fraction * max_score as f32).round())
+            .unwrap_or_default()
+    }
+
+    pub fn pangrams_found(&self) -> usize {
+        self.found
+            .iter()
+            .filter(|word| self.puzzle.pangrams().contains(word))
+            .count()
+    }
+
+    /// A summary of the game to share that does not give away any words: the
+    /// rank, score, and counts of words and pangrams found, and a grid of
+    /// how many of the words of each starting letter (rows) and length
+    /// (columns) have been found
+    pub fn share_text(&self, locale: Locale) -> String {
+        // Name of the game, in every locale
+        const TITLE: &str = "Colemnagrafía";
+
+        let puzzle = &self.puzzle;
+        // Daily puzzles before launch have no number, only a date
+        let label = match puzzle.number() {
+            Some(number) => Some(format!("#{}", number)),
+            None => puzzle
+                .day()
+                .and_then(|day| days::date_for_day(day).ok())
+                .map(|date| date.to_string()),
+        };
+        let mut lines = vec![match label {
+            Some(label) => format!("{} {} 🐝", TITLE, label),
+            None => format!("{} 🐝", TITLE),
+        }];
+
+        let rank = locale.rank_name(self.rank());
+        let (score, max_score) = (self.score(), self.max_score());
+        let words = self.findable().len();
+        let pangrams = puzzle.pangrams().len();
+        let (found_words, found_pangrams) = (self.found.len(), self.pangrams_found());
+        lines.push(match locale {
+            Locale::Es => format!("{} · {}/{} puntos", rank, score, max_score),
+            Locale::En => format!("{} · {}/{} points", rank, score, max_score),
+        });
+        lines.push(match locale {
+            Locale::Es => format!(
+                "{}/{} palabras · {}/{} pangramas",
+                found_words, words, found_pangrams, pangrams
+            ),
+            Locale::En => format!(
+                "{}/{} words · {}/{} pangrams",
+                found_words, words, found_pangrams, pangrams
+            ),
+        });
+
+        if !self.hints.is_empty() {
+            let hints = self.hints.len();
+            let cost = self.hint_cost();
+            lines.push(match (locale, self.deduct_hint_costs) {
+                (Locale::Es, false) => format!("💡 {} {}", hints, plural(hints, "pista", "pistas")),
+                (Locale::Es, true) => format!(
+                    "💡 {} {} (−{} puntos)",
+                    hints,
+                    plural(hints, "pista", "pistas"),
+                    cost
+                ),
+                (Locale::En, false) => format!("💡 {} {}", hints, plural(hints, "hint", "hints")),
+                (Locale::En, true) => format!(
+                    "💡 {} {} (−{} points)",
+                    hints,
+                    plural(hints, "hint", "hints"),
+                    cost
+                ),
+            });
+        }
+
+        lines.extend(self.grid());
+        lines.join("\n")
+    }
+
+    /// Rows of the share text's grid: 🟩 all found, 🟨 some found, ⬜ none
+    /// found, ⬛ no words
+    fn grid(&self) -> Vec<String> {
+        let language = self.puzzle.language();
+        let found: HashSet<&String> = HashSet::from_iter(&self.found);
+        // Map (first letter, length) -> (found, total)
+        let mut counts: BTreeMap<(char, usize), (usize, usize)> = BTreeMap::new();
+        self.findable().into_iter().for_each(|word| {
+            let Some(first) = language.normalize(word).chars().next() else {
+                return;
+            };
+            let count = counts.entry((first, word.chars().count())).or_default();
+            count.1 += 1;
+            if found.contains(word) {
+                count.0 += 1;
+            }
+        });
+        let lengths = counts.keys().map(|(_, length)| *length);
+        let (Some(shortest), Some(longest)) = (lengths.clone().min(), lengths.max()) else {
+            return Vec::new();
+        };
+
+        let mut letters: Vec<char> = self
+            .puzzle
+            .letters()
+            .iter()
+            .flat_map(|c| c.to_lowercase())
+            .collect();
+        letters.sort();
+        letters
+            .into_iter()
+            .map(|letter| {
+                let cells: String = (shortest..=longest)
+                    .map(|length| match counts.get(&(letter, length)) {
+                        None => '⬛',
+                        Some((0, _)) => '⬜',
+                        Some((found, total)) if found == total => '🟩',
+                        Some(_) => '🟨',
+                    })
+                    .collect();
+                format!("{} {}", letter.to_uppercase(), cells)
+            })
+            .collect()
+    }
+}
+
+fn plural<'a>(count: usize, singular: &'a str, plural: &'a str) -> &'a str {
+    match count {
+        1 => singular,
+        _ => plural,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{days::LAUNCH_DAY, testing};
+
+    /// A game of the seventh daily puzzle
+    fn session() -> GameSession {
+        let puzzle = testing::puzzle().with_day(LAUNCH_DAY + 6);
+        let mut session = GameSession::new(puzzle);
+        session.found = vec!["seguido".into(), "dios".into(), "dedos".into()];
+        session
+    }
+
+    /// The share text must not give away any word, found or not
+    fn assert_spoiler_free(session: &GameSession, text: &str) {
+        let text = text.to_lowercase();
+        session
+            .puzzle
+            .all_words()
+            .chain(session.puzzle.normalized_words())
+            .for_each(|word| assert!(!text.contains(word.as_str()), "{} in {}", word, text));
+    }
+
+    #[test]
+    fn share_text_es() {
+        let session = session();
+        let text = session.share_text(Locale::Es);
+        assert_eq!(
+            text,
+            concat!(
+                "Colemnagrafía #7 🐝\n",
+                "Subiendo · 20/342 puntos\n",
+                "3/82 palabras · 1/2 pangramas\n",
+                "D 🟨🟨⬜⬜⬜\n",
+                "E ⬛⬛⬛⬜⬛\n",
+                "G ⬜⬜⬛⬛⬛\n",
+                "I ⬜⬜⬛⬛⬛\n",
+                "O ⬜⬜⬜⬜⬛\n",
+                "S ⬜⬜⬜🟨⬜\n",
+                "U ⬛⬛⬛⬛⬛",
+            )
+        );
+        assert_spoiler_free(&session, &text);
+    }
+
+    #[test]
+    fn share_text_en() {
+        let session = session();
+        let text = session.share_text(Locale::En);
+        assert_eq!(
+            text,
+            concat!(
+                "Colemnagrafía #7 🐝\n",
+                "Moving Up · 20/342 points\n",
+                "3/82 words · 1/2 pangrams\n",
+                "D 🟨🟨⬜⬜⬜\n",
+                "E ⬛⬛⬛⬜⬛\n",
+                "G ⬜⬜⬛⬛⬛\n",
+                "I ⬜⬜⬛⬛⬛\n",
+                "O ⬜⬜⬜⬜⬛\n",
+                "S ⬜⬜⬜🟨⬜\n",
+                "U ⬛⬛⬛⬛⬛",
+            )
+        );
+        assert_spoiler_free(&session, &text);
+    }
+
+    #[test]
+    fn share_text_before_launch() {
+        let mut session = session();
+        session.puzzle = testing::puzzle().with_day(19_800);
+        let text = session.share_text(Locale::En);
+        assert_eq!(text.lines().next(), Some("Colemnagrafía 2024-03-18 🐝"));
+        session.puzzle = testing::puzzle();
+        let text = session.share_text(Locale::En);
+        assert_eq!(text.lines().next(), Some("Colemnagrafía 🐝"));
+    }
+}
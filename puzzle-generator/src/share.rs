@@ -0,0 +1,236 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde::Serialize;
+use words::Language;
+
+use crate::{
+    accents::AccentPolicy,
+    custom,
+    puzzles::{self, Puzzle},
+    scoring::ScoringPreset,
+    Error,
+};
+
+/// Version of the share code layout, the code's first byte
+const FORMAT: u8 = 1;
+/// Format, language and scoring and accents, letters, word list version,
+/// checksum
+const LENGTH: usize = 1 + 1 + 4 + 4 + 2;
+
+/// Why a share code could not be read
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, thiserror::Error)]
+#[serde(tag = "code", rename_all = "kebab-case")]
+pub enum ShareCodeError {
+    #[error("The code has characters that are not part of a code")]
+    InvalidCharacters,
+    #[error("The code is too short or too long")]
+    WrongLength,
+    #[error("The code is corrupted")]
+    BadChecksum,
+    #[error("The code was made by a newer version of the game")]
+    UnknownFormat,
+    #[error("The code has an unknown language, scoring, or accent policy")]
+    UnknownSettings,
+    #[error("The code's letters are not a valid puzzle")]
+    InvalidLetters,
+}
+
+/// Everything needed to rebuild a puzzle, as a short URL-safe code like
+/// `AQFAFQAbmpXgzkmu`. The letters are a bitmask with the center's index, and
+/// the word list is identified by a hash of its version, so a code only opens
+/// with the same word list it was made with.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ShareCode {
+    pub language: Language,
+    /// The letters, center first
+    pub letters: Vec<char>,
+    pub scoring: ScoringPreset,
+    pub accents: AccentPolicy,
+    /// Hash of the word list version
+    pub version: u32,
+}
+
+impl ShareCode {
+    pub fn from_puzzle(puzzle: &Puzzle) -> Result<ShareCode, Error> {
+        Ok(ShareCode {
+            language: puzzle.language(),
+            letters: puzzle.letters().to_vec(),
+            scoring: puzzle.scoring(),
+            accents: puzzle.accents(),
+            version: version_hash(puzzle.language())?,
+        })
+    }
+
+    pub fn encode(&self) -> String {
+        let mut bytes = Vec::with_capacity(LENGTH);
+        bytes.push(FORMAT);
+        bytes.push(
+            (index_of(&Language::ALL, self.language) << 4)
+                | (index_of(&AccentPolicy::ALL, self.accents) << 2)
+                | index_of(&ScoringPreset::ALL, self.scoring),
+        );
+
+        // 26 bits of letters and 5 bits of the center
+        let mask = self
+            .letters
+            .iter()
+            .fold(0u32, |mask, &c| mask | 1 << (c as u32 - 'a' as u32));
+        let center = self.letters[0] as u32 - 'a' as u32;
+        bytes.extend((mask | center << 26).to_be_bytes());
+
+        bytes.extend(self.version.to_be_bytes());
+        bytes.extend(&checksum(&bytes).to_be_bytes()[2..]);
+        URL_SAFE_NO_PAD.encode(bytes)
+    }
+
+    pub fn decode(code: &str) -> Result<ShareCode, ShareCodeError> {
+        let bytes = URL_SAFE_NO_PAD
+            .decode(code.trim())
+            .map_err(|_| ShareCodeError::InvalidCharacters)?;
+        if bytes.len() != LENGTH {
+            return Err(ShareCodeError::WrongLength);
+        }
+        let (data, sum) = bytes.split_at(LENGTH - 2);
+        if checksum(data).to_be_bytes()[2..] != *sum {
+            return Err(ShareCodeError::BadChecksum);
+        }
+        if data[0] != FORMAT {
+            return Err(ShareCodeError::UnknownFormat);
+        }
+
+        let language = *Language::ALL
+            .get((data[1] >> 4) as usize)
+            .ok_or(ShareCodeError::UnknownSettings)?;
+        let accents = *AccentPolicy::ALL
+            .get((data[1] >> 2 & 0x3) as usize)
+            .ok_or(ShareCodeError::UnknownSettings)?;
+        let scoring = *ScoringPreset::ALL
+            .get((data[1] & 0x3) as usize)
+            .ok_or(ShareCodeError::UnknownSettings)?;
+
+        let packed = u32::from_be_bytes([data[2], data[3], data[4], data[5]]);
+        let letter = |i: u32| char::from(b'a' + i as u8);
+        let center = packed >> 26;
+        let mask = packed & ((1 << 26) - 1);
+        if center >= 26 || mask.count_ones() != 7 || mask & 1 << center == 0 {
+            return Err(ShareCodeError::InvalidLetters);
+        }
+        let mut letters = vec![letter(center)];
+        letters.extend(
+            (0..26)
+                .filter(|&i| i != center && mask & 1 << i != 0)
+                .map(letter),
+        );
+
+        Ok(ShareCode {
+            language,
+            letters,
+            scoring,
+            accents,
+            version: u32::from_be_bytes([data[6], data[7], data[8], data[9]]),
+        })
+    }
+
+    /// Build the puzzle of the code, which must have been made with the
+    /// current word list
+    pub fn to_puzzle(&self) -> Result<Puzzle, Error> {
+        let version = version_hash(self.language)?;
+        if self.version != version {
+            return Err(Error::VersionMismatch {
+                expected: format!("{:08x}", version),
+                found: format!("{:08x}", self.version),
+            });
+        }
+
+        let letters: String = self.letters.iter().collect();
+        let letters = custom::puzzle_letters(self.language, &letters, &letters[..1])?;
+        let puzzle = puzzles::create_puzzle_with_bounds(self.language, letters, 0, usize::MAX)?;
+        Ok(puzzle.with_scoring(self.scoring).with_accents(self.accents))
+    }
+}
+
+/// Hash of the language's word list version
+fn version_hash(language: Language) -> Result<u32, Error> {
+    Ok(fnv1a(puzzles::dictionary(language)?.version.as_bytes()))
+}
+
+fn checksum(bytes: &[u8]) -> u32 {
+    fnv1a(bytes)
+}
+
+pub(crate) fn fnv1a(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0x811c9dc5, |hash, &byte| {
+        (hash ^ byte as u32).wrapping_mul(0x01000193)
+    })
+}
+
+fn index_of<T: PartialEq>(all: &[T], value: T) -> u8 {
+    all.iter().position(|v| *v == value).unwrap_or_default() as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn share_code() -> ShareCode {
+        let puzzle = testing::puzzle()
+            .with_scoring(ScoringPreset::Flat)
+            .with_accents(AccentPolicy::RequireAccent);
+        ShareCode::from_puzzle(&puzzle).unwrap()
+    }
+
+    /// Re-encode a code's bytes after changing them
+    fn tamper(code: &str, change: impl FnOnce(&mut Vec<u8>)) -> String {
+        let mut bytes = URL_SAFE_NO_PAD.decode(code).unwrap();
+        change(&mut bytes);
+        URL_SAFE_NO_PAD.encode(bytes)
+    }
+
+    #[test]
+    fn round_trip() {
+        let code = share_code();
+        let puzzle = ShareCode::decode(&code.encode())
+            .unwrap()
+            .to_puzzle()
+            .unwrap();
+
+        let mut letters = puzzle.letters().to_vec();
+        assert_eq!(letters[0], 'd');
+        letters.sort();
+        assert_eq!(letters, ['d', 'e', 'g', 'i', 'o', 's', 'u']);
+        assert_eq!(puzzle.scoring(), ScoringPreset::Flat);
+        assert_eq!(puzzle.accents(), AccentPolicy::RequireAccent);
+    }
+
+    #[test]
+    fn flipped_byte() {
+        let code = tamper(&share_code().encode(), |bytes| bytes[3] ^= 0x10);
+        assert_eq!(ShareCode::decode(&code), Err(ShareCodeError::BadChecksum));
+    }
+
+    #[test]
+    fn truncated() {
+        let code = tamper(&share_code().encode(), |bytes| bytes.truncate(LENGTH - 1));
+        assert_eq!(ShareCode::decode(&code), Err(ShareCodeError::WrongLength));
+    }
+
+    #[test]
+    fn invalid_characters() {
+        let code = format!("!{}", &share_code().encode()[1..]);
+        assert_eq!(
+            ShareCode::decode(&code),
+            Err(ShareCodeError::InvalidCharacters)
+        );
+    }
+
+    #[test]
+    fn other_word_list() {
+        let mut code = share_code();
+        code.version = fnv1a(b"es-general-00000000");
+        let decoded = ShareCode::decode(&code.encode()).unwrap();
+        assert!(matches!(
+            decoded.to_puzzle(),
+            Err(Error::VersionMismatch { .. })
+        ));
+    }
+}
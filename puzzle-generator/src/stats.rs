@@ -0,0 +1,200 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use words::Language;
+
+use crate::{save::SaveState, session::SCORE_RANKS};
+
+/// Index of Genius into [SCORE_RANKS]
+pub const GENIUS_RANK: usize = SCORE_RANKS.len() - 2;
+/// Index of Queen Bee, finding every word, into [SCORE_RANKS]
+pub const QUEEN_BEE_RANK: usize = SCORE_RANKS.len() - 1;
+/// Number of most missed words in the statistics
+pub const MOST_MISSED: usize = 10;
+
+/// The progress of a played day, e.g. to decorate the calendar
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct DayProgress {
+    pub day: u32,
+    pub score: u32,
+    pub max_score: u32,
+    /// Index of the rank reached into [SCORE_RANKS]
+    pub rank: usize,
+    pub words_found: usize,
+}
+
+/// A word left unfound in several played puzzles
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct MissedWord {
+    pub word: String,
+    pub missed: usize,
+}
+
+/// A player's statistics over the days of a language with some words found
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Stats {
+    pub days_played: usize,
+    /// Consecutive days played up to today, or up to yesterday if today has
+    /// not been played yet
+    pub current_streak: usize,
+    pub longest_streak: usize,
+    /// Days that reached Genius or better
+    pub genius: usize,
+    pub queen_bee: usize,
+    /// Average index of the rank reached into [SCORE_RANKS]
+    pub average_rank: f32,
+    pub words_found: usize,
+    /// The words left unfound most often, most missed first
+    pub most_missed: Vec<MissedWord>,
+    /// Sorted by day
+    pub days: Vec<DayProgress>,
+}
+
+impl Stats {
+    /// Statistics of the save states of a language, with `today` the current
+    /// puzzle day. Save states whose puzzle cannot be rebuilt are skipped.
+    pub fn from_states(states: &[SaveState], language: Language, today: u32) -> Stats {
+        let mut sessions: Vec<_> = states
+            .iter()
+            .filter(|state| state.language == language && state.day <= today)
+            .filter_map(|state| state.to_session().ok())
+            .map(|(session, _)| session)
+            .filter(|session| !session.found.is_empty())
+            .collect();
+        sessions.sort_by_key(|session| session.puzzle.day());
+        sessions.dedup_by_key(|session| session.puzzle.day());
+
+        let mut missed: HashMap<String, usize> = HashMap::new();
+        let days: Vec<DayProgress> = sessions
+            .iter()
+            .filter_map(|session| {
+                session
+                    .findable()
+                    .into_iter()
+                    .filter(|word| !session.found.contains(word))
+                    .for_each(|word| *missed.entry(word.clone()).or_default() += 1);
+                Some(DayProgress {
+                    day: session.puzzle.day()?,
+                    score: session.score(),
+                    max_score: session.max_score(),
+                    rank: session.rank(),
+                    words_found: session.found.len(),
+                })
+            })
+            .collect();
+        if days.is_empty() {
+            return Stats::default();
+        }
+
+        let mut most_missed: Vec<MissedWord> = missed
+            .into_iter()
+            .filter(|(_, missed)| *missed > 1)
+            .map(|(word, missed)| MissedWord { word, missed })
+            .collect();
+        most_missed.sort_by(|a, b| b.missed.cmp(&a.missed).then(a.word.cmp(&b.word)));
+        most_missed.truncate(MOST_MISSED);
+
+        let played: Vec<u32> = days.iter().map(|day| day.day).collect();
+        Stats {
+            days_played: days.len(),
+            current_streak: current_streak(&played, today),
+            longest_streak: longest_streak(&played),
+            genius: days.iter().filter(|day| day.rank >= GENIUS_RANK).count(),
+            queen_bee: days.iter().filter(|day| day.rank >= QUEEN_BEE_RANK).count(),
+            average_rank: days.iter().map(|day| day.rank as f32).sum::<f32>() / days.len() as f32,
+            words_found: days.iter().map(|day| day.words_found).sum(),
+            most_missed,
+            days,
+        }
+    }
+}
+
+/// Length of the run of consecutive days ending at the last of the sorted
+/// days
+fn last_run(played: &[u32]) -> usize {
+    let Some(&last) = played.last() else {
+        return 0;
+    };
+    played
+        .iter()
+        .rev()
+        .zip(0..)
+        .take_while(|(&day, offset)| day + offset == last)
+        .count()
+}
+
+fn current_streak(played: &[u32], today: u32) -> usize {
+    match played.last() {
+        Some(&last) if last == today || last + 1 == today => last_run(played),
+        _ => 0,
+    }
+}
+
+fn longest_streak(played: &[u32]) -> usize {
+    let (mut longest, mut run) = (0, 0);
+    played.iter().enumerate().for_each(|(i, &day)| {
+        run = match i > 0 && played[i - 1] + 1 == day {
+            true => run + 1,
+            false => 1,
+        };
+        longest = longest.max(run);
+    });
+    longest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        days::{self, DayZone},
+        testing,
+    };
+
+    fn state(day: u32) -> SaveState {
+        testing::state(day, &["dedos"], 0)
+    }
+
+    #[test]
+    fn gap_breaks_streak() {
+        let played = [10, 11, 12, 14, 15];
+        assert_eq!(current_streak(&played, 15), 2);
+        assert_eq!(longest_streak(&played), 3);
+    }
+
+    #[test]
+    fn streak_waits_for_today() {
+        assert_eq!(current_streak(&[10, 11], 12), 2);
+        assert_eq!(current_streak(&[10, 11], 13), 0);
+    }
+
+    #[test]
+    fn duplicate_days_count_once() {
+        let stats = Stats::from_states(&[state(10), state(10), state(11)], Language::Es, 11);
+        assert_eq!(stats.days_played, 2);
+        assert_eq!(stats.current_streak, 2);
+        assert_eq!(stats.words_found, 2);
+    }
+
+    #[test]
+    fn future_days_are_ignored() {
+        let stats = Stats::from_states(&[state(10), state(11), state(12)], Language::Es, 11);
+        assert_eq!(stats.days_played, 2);
+        assert_eq!(stats.days.last().map(|day| day.day), Some(11));
+    }
+
+    #[test]
+    fn streak_follows_the_zone_midnight() {
+        // 23:59 and 00:01 in Madrid, the same day in UTC
+        let zone: DayZone = "Europe/Madrid".parse().unwrap();
+        let (before, after) = (1_710_802_740_000, 1_710_802_860_000);
+        let played = [
+            days::puzzle_day_for(before, &zone).unwrap(),
+            days::puzzle_day_for(after, &zone).unwrap(),
+        ];
+        assert_eq!(played, [19_800, 19_801]);
+        assert_eq!(days::puzzle_day_for(after, &DayZone::Utc).unwrap(), 19_800);
+
+        let states = played.map(state);
+        let stats = Stats::from_states(&states, Language::Es, played[1]);
+        assert_eq!(stats.current_streak, 2);
+    }
+}
@@ -0,0 +1,427 @@
+use serde::{Deserialize, Serialize};
+use words::Language;
+
+use crate::{
+    accents,
+    near_miss::{self, NearMiss},
+    session::{GameSession, Locale},
+};
+
+/// How a found form relates to its lemma
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Relation {
+    /// The form is the lemma itself, e.g. "cantar"
+    Lemma,
+    /// The form is a form of a lemma that is also in the list, e.g. "cantaba"
+    Form,
+    /// The form is in the list but its lemma is not, e.g. because the lemma
+    /// has letters outside of the puzzle
+    LemmaNotInList,
+}
+
+/// How many of a lemma's forms have been found
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct LemmaProgress {
+    pub lemma: String,
+    /// Found forms, sorted
+    pub found: Vec<String>,
+    pub total: usize,
+}
+
+/// A form found by a submission
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct FoundForm {
+    pub form: String,
+    pub score: u32,
+    pub relation: Relation,
+    /// Progress of the form's lemma, including the form
+    pub progress: LemmaProgress,
+}
+
+/// A submitted word that was not in the list, for curating the word list
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Rejection {
+    /// The word as typed, lowercased
+    pub word: String,
+    pub language: Language,
+    pub day: Option<u32>,
+    pub near_miss: Option<NearMiss>,
+}
+
+/// The result of submitting a word
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "result", rename_all = "kebab-case")]
+pub enum Feedback {
+    TooShort,
+    MissingCenter,
+    NotInList {
+        /// Why the word was close, if anything is known about it
+        near_miss: Option<NearMiss>,
+    },
+    AlreadyFound,
+    /// The word is in the list, but with strict accents it must be typed with
+    /// the accents of one of its forms
+    WrongAccent {
+        /// Whether the word was typed without any accents
+        missing: bool,
+    },
+    /// The forms of the submitted word awarded by the puzzle's accent policy,
+    /// e.g. "papa" finds "papa" and "papá"
+    Found {
+        forms: Vec<FoundForm>,
+        /// Points for typing the accents, included in the score
+        #[serde(default)]
+        bonus: u32,
+        score: u32,
+    },
+}
+
+impl Feedback {
+    pub fn text(&self, locale: Locale) -> String {
+        let forms = match self {
+            Feedback::TooShort => return locale.text("Muy corta", "Too short").into(),
+            Feedback::MissingCenter => {
+                return locale
+                    .text("Falta la letra central", "Missing center letter")
+                    .into()
+            }
+            Feedback::NotInList { near_miss } => {
+                let text = locale.text("No está en la lista", "Not in word list");
+                return match near_miss {
+                    Some(near_miss) => format!("{}: {}", text, near_miss.text(locale)),
+                    None => text.into(),
+                };
+            }
+            Feedback::AlreadyFound => return locale.text("Ya encontrada", "Already found").into(),
+            Feedback::WrongAccent { missing: true } => {
+                return locale
+                    .text(
+                        "Palabra correcta, pero le falta la tilde",
+                        "Right word, but missing its accent",
+                    )
+                    .into()
+            }
+            Feedback::WrongAccent { missing: false } => {
+                return locale
+                    .text(
+                        "Palabra correcta, pero con la tilde equivocada",
+                        "Right word, but with the wrong accent",
+                    )
+                    .into()
+            }
+            Feedback::Found { forms, .. } => forms,
+        };
+
+        let mut lines: Vec<String> = forms
+            .iter()
+            .map(|found| {
+                let LemmaProgress {
+                    lemma,
+                    found: forms,
+                    total,
+                } = &found.progress;
+                let count = forms.len();
+                let relation = match (found.relation, locale) {
+                    (Relation::Lemma, Locale::Es) => format!("{} ({} de {})", lemma, count, total),
+                    (Relation::Lemma, Locale::En) => {
+                        format!("{} ({} of {} found)", lemma, count, total)
+                    }
+                    (Relation::Form, Locale::Es) => {
+                        format!("nueva forma de {} ({} de {})", lemma, count, total)
+                    }
+                    (Relation::Form, Locale::En) => {
+                        format!("new form of {} ({} of {} found)", lemma, count, total)
+                    }
+                    (Relation::LemmaNotInList, Locale::Es) => format!(
+                        "forma de {}, que no está en la lista ({} de {})",
+                        lemma, count, total
+                    ),
+                    (Relation::LemmaNotInList, Locale::En) => format!(
+                        "form of {}, which is not in the list ({} of {} found)",
+                        lemma, count, total
+                    ),
+                };
+                format!("+{} {}: {}", found.score, found.form, relation)
+            })
+            .collect();
+        if let Feedback::Found { bonus, .. } = self {
+            if *bonus > 0 {
+                lines.push(format!("+{} {}", bonus, locale.text("acentos", "accents")));
+            }
+        }
+        lines.join("\n")
+    }
+}
+
+impl GameSession {
+    /// Submit a word, adding the forms awarded by the puzzle's accent policy,
+    /// or only the form typed with strict accents, to the found words if it
+    /// is in the list
+    pub fn submit(&mut self, word: &str) -> Feedback {
+        let language = self.puzzle.language();
+        let typed = word.trim().to_lowercase();
+        let normalized = language.normalize(&typed);
+        if normalized.chars().count() < 4 {
+            return Feedback::TooShort;
+        }
+        let Some(variants) = self.puzzle.forms_of_word(&normalized) else {
+            let center = self.puzzle.letters()[0].to_lowercase().next();
+            if !normalized.chars().any(|c| Some(c) == center) {
+                return Feedback::MissingCenter;
+            }
+            return self.reject(word);
+        };
+        let awarded = match self.strict_accents {
+            true if variants.contains(&typed) => Some((
+                vec![typed.clone()],
+                accents::strict_bonus(&self.puzzle, &typed),
+            )),
+            true => {
+                return Feedback::WrongAccent {
+                    missing: typed == normalized,
+                }
+            }
+            false => self.puzzle.accents().award(&self.puzzle, &typed, variants),
+        };
+        let Some((forms, bonus)) = awarded else {
+            return self.reject(word);
+        };
+        let forms: Vec<String> = forms
+            .into_iter()
+            .filter(|form| !self.found.contains(form))
+            .collect();
+        if forms.is_empty() {
+            return Feedback::AlreadyFound;
+        }
+
+        forms
+            .iter()
+            .for_each(|form| self.found.insert(0, form.clone()));
+        self.bonus += bonus;
+
+        let forms: Vec<FoundForm> = forms
+            .into_iter()
+            .filter_map(|form| {
+                let lemma = self.puzzle.lemma(&form)?.clone();
+                let relation = if form == lemma {
+                    Relation::Lemma
+                } else if self.puzzle.lemma(&lemma).is_some() {
+                    Relation::Form
+                } else {
+                    Relation::LemmaNotInList
+                };
+                Some(FoundForm {
+                    score: self.puzzle.score(&form),
+                    relation,
+                    progress: self.lemma_progress(&lemma)?,
+                    form,
+                })
+            })
+            .collect();
+        let score = forms.iter().map(|found| found.score).sum::<u32>() + bonus;
+        Feedback::Found {
+            forms,
+            bonus,
+            score,
+        }
+    }
+
+    /// Record a submission that is not in the list
+    fn reject(&mut self, word: &str) -> Feedback {
+        let near_miss = near_miss::diagnose(&self.puzzle, word);
+        self.rejections.push(Rejection {
+            word: word.trim().to_lowercase(),
+            language: self.puzzle.language(),
+            day: self.puzzle.day(),
+            near_miss: near_miss.clone(),
+        });
+        Feedback::NotInList { near_miss }
+    }
+
+    /// The rejected submissions as JSON lines, to be collected and reviewed
+    /// with `crea-explorer review-rejections`
+    pub fn rejection_log(&self) -> String {
+        self.rejections
+            .iter()
+            .filter_map(|rejection| serde_json::to_string(rejection).ok())
+            .map(|line| line + "\n")
+            .collect()
+    }
+
+    /// How many of a lemma's forms have been found
+    pub fn lemma_progress(&self, lemma: &str) -> Option<LemmaProgress> {
+        let forms = self.puzzle.forms_of(lemma)?;
+        let mut found: Vec<String> = self
+            .found
+            .iter()
+            .filter(|word| forms.contains(*word))
+            .cloned()
+            .collect();
+        found.sort();
+        found.dedup();
+        Some(LemmaProgress {
+            lemma: lemma.to_string(),
+            found,
+            total: forms.len(),
+        })
+    }
+
+    /// Progress of every lemma with at least one form found, sorted by lemma
+    pub fn progress_by_lemma(&self) -> Vec<LemmaProgress> {
+        let mut lemmas: Vec<&String> = self
+            .found
+            .iter()
+            .filter_map(|word| self.puzzle.lemma(word))
+            .collect();
+        lemmas.sort();
+        lemmas.dedup();
+        lemmas
+            .into_iter()
+            .filter_map(|lemma| self.lemma_progress(lemma))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{accents::ACCENT_BONUS, testing};
+
+    /// The relation and lemma progress of each form found by a submission
+    fn found(feedback: &Feedback) -> Vec<(&str, Relation, &str, usize, usize)> {
+        let Feedback::Found { forms, .. } = feedback else {
+            panic!("{:?} found nothing", feedback);
+        };
+        forms
+            .iter()
+            .map(|found| {
+                let progress = &found.progress;
+                let lemma = progress.lemma.as_str();
+                (
+                    found.form.as_str(),
+                    found.relation,
+                    lemma,
+                    progress.found.len(),
+                    progress.total,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn forms_relate_to_their_lemma() {
+        let mut session = GameSession::new(testing::puzzle());
+        let dedos = session.submit("Dedos");
+        assert_eq!(found(&dedos), [("dedos", Relation::Form, "dedo", 1, 2)]);
+        assert_eq!(
+            dedos.text(Locale::En),
+            "+5 dedos: new form of dedo (1 of 2 found)"
+        );
+        let dedo = session.submit("dedo");
+        assert_eq!(found(&dedo), [("dedo", Relation::Lemma, "dedo", 2, 2)]);
+        assert_eq!(dedo.text(Locale::Es), "+1 dedo: dedo (2 de 2)");
+
+        // Both variants of dudo, whose lemma needs an r, are found at once
+        let dudo = session.submit("dudo");
+        assert_eq!(
+            found(&dudo),
+            [
+                ("dudo", Relation::LemmaNotInList, "dudar", 2, 6),
+                ("dudó", Relation::LemmaNotInList, "dudar", 2, 6),
+            ]
+        );
+        assert_eq!(
+            dudo.text(Locale::En).lines().next(),
+            Some("+1 dudo: form of dudar, which is not in the list (2 of 6 found)")
+        );
+
+        let progress: Vec<(String, usize)> = session
+            .progress_by_lemma()
+            .into_iter()
+            .map(|progress| (progress.lemma, progress.found.len()))
+            .collect();
+        assert_eq!(progress, [("dedo".into(), 2), ("dudar".into(), 2)]);
+    }
+
+    #[test]
+    fn submissions_that_find_nothing() {
+        let mut session = GameSession::new(testing::puzzle());
+        assert!(matches!(session.submit("dio"), Feedback::TooShort));
+        assert!(matches!(session.submit("sois"), Feedback::MissingCenter));
+        assert!(matches!(session.submit("dedo"), Feedback::Found { .. }));
+        assert!(matches!(session.submit(" DEDO "), Feedback::AlreadyFound));
+        assert_eq!(session.found, ["dedo"]);
+    }
+
+    #[test]
+    fn strict_accents() {
+        let mut session = GameSession::new(testing::puzzle());
+        session.strict_accents = true;
+        let dudo = session.submit("dudo");
+        assert!(matches!(
+            dudo,
+            Feedback::Found {
+                bonus: 0,
+                score: 1,
+                ..
+            }
+        ));
+        assert_eq!(session.found, ["dudo"]);
+        // The other variant is still to be found
+        assert!(matches!(
+            session.submit("dúdo"),
+            Feedback::WrongAccent { missing: false }
+        ));
+        assert!(matches!(
+            session.submit("ideo"),
+            Feedback::WrongAccent { missing: true }
+        ));
+        let accented = session.submit("dudó");
+        assert!(matches!(
+            accented,
+            Feedback::Found {
+                bonus: ACCENT_BONUS,
+                score: 3,
+                ..
+            }
+        ));
+        assert_eq!(accented.text(Locale::En).lines().last(), Some("+2 accents"));
+        assert_eq!(session.found, ["dudó", "dudo"]);
+        assert_eq!(session.score(), 1 + 1 + ACCENT_BONUS);
+
+        assert_eq!(session.findable().len(), session.puzzle.word_count());
+        assert_eq!(
+            session.max_score(),
+            accents::strict_max_score(&session.puzzle)
+        );
+        assert!(session.max_score() > session.puzzle.max_score());
+    }
+
+    #[test]
+    fn rejections_are_logged() {
+        let mut session = GameSession::new(testing::puzzle().with_day(19_800));
+        session.submit("Sudoku");
+        session.submit("dosed");
+        // Words the puzzle does not allow are not for curating
+        session.submit("dio");
+        session.submit("sois");
+        session.submit("dedo");
+
+        let log: Vec<Rejection> = session
+            .rejection_log()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        let words: Vec<(&str, Option<u32>)> = log
+            .iter()
+            .map(|rejection| (rejection.word.as_str(), rejection.day))
+            .collect();
+        assert_eq!(words, [("sudoku", Some(19_800)), ("dosed", Some(19_800))]);
+        assert!(matches!(
+            &log[0].near_miss,
+            Some(NearMiss::LetterNotInHive { letters, in_word_list: false }) if letters == &['k']
+        ));
+        assert_eq!(log[1].near_miss, None);
+    }
+}
@@ -0,0 +1,80 @@
+//! Fixtures shared by the tests
+
+use words::{Categoria, Dictionary, Exclusion, Language};
+
+use crate::{
+    puzzles::{self, Puzzle},
+    save::SaveState,
+    session::GameSession,
+};
+
+/// Three letter sets, each with four words that use all of its letters, and
+/// an accented variant of one of them
+const WORDS: &[(&str, &str)] = &[
+    ("abcdefg", "abcdefg"),
+    ("bacdefg", "abcdefg"),
+    ("cabdefg", "abcdefg"),
+    ("dabcefg", "abcdefg"),
+    ("dábcefg", "abcdefg"),
+    ("hijklmn", "hijklmn"),
+    ("ihjklmn", "hijklmn"),
+    ("jhiklmn", "hijklmn"),
+    ("khijlmn", "hijklmn"),
+    ("opqrstu", "opqrstu"),
+    ("poqrstu", "opqrstu"),
+    ("qoprstu", "opqrstu"),
+    ("roqpstu", "opqrstu"),
+];
+
+const PANGRAMS: &[&str] = &[
+    "abcdefg", "bacdefg", "cabdefg", "dabcefg", "hijklmn", "ihjklmn", "jhiklmn", "khijlmn",
+    "opqrstu", "poqrstu", "qoprstu", "roqpstu",
+];
+
+/// The words of the first letter set are easy, of the second normal, and of
+/// the third hard. The accented variant is the more frequent.
+const METADATA: &[(Categoria, f32, f32, f32)] = &[
+    (Categoria::V, 100.0, 330.0, 0.1),
+    (Categoria::V, 80.0, 330.0, 0.1),
+    (Categoria::V, 60.0, 330.0, 0.1),
+    (Categoria::V, 40.0, 330.0, 0.1),
+    (Categoria::V, 50.0, 330.0, 0.1),
+    (Categoria::N, 10.0, 28.0, 0.4),
+    (Categoria::N, 8.0, 28.0, 0.4),
+    (Categoria::N, 6.0, 28.0, 0.4),
+    (Categoria::N, 4.0, 28.0, 0.4),
+    (Categoria::A, 1.0, 2.8, 0.8),
+    (Categoria::A, 0.8, 2.8, 0.8),
+    (Categoria::A, 0.6, 2.8, 0.8),
+    (Categoria::A, 0.4, 2.8, 0.8),
+];
+
+const EXCLUDED: &[(&str, &str, Exclusion)] = &[];
+
+/// A small word list with metadata
+pub(crate) fn dictionary() -> Dictionary {
+    Dictionary {
+        language: Language::Es,
+        variant: "test",
+        words: WORDS,
+        pangrams: PANGRAMS,
+        metadata: METADATA,
+        excluded: EXCLUDED,
+        version: "es-test-0",
+    }
+}
+
+/// The Spanish puzzle of the letters d (the center), s, e, g, i, o, and u,
+/// with all of their words
+pub(crate) fn puzzle() -> Puzzle {
+    let letters = vec!['d', 's', 'e', 'g', 'i', 'o', 'u'];
+    puzzles::create_puzzle_with_bounds(Language::Es, letters, 0, usize::MAX).unwrap()
+}
+
+/// The save state of [puzzle] as the puzzle of a day, with some words found,
+/// started at 0 and saved at `updated`
+pub(crate) fn state(day: u32, found: &[&str], updated: u64) -> SaveState {
+    let mut session = GameSession::new(puzzle().with_day(day));
+    session.found = found.iter().map(|word| word.to_string()).collect();
+    SaveState::from_session(&session, 0, updated).unwrap()
+}
@@ -6,6 +6,7 @@ import {
 	FONTS,
 	SIZES,
 	getDaysSinceEpoch,
+	getToday,
 	triangle,
 	wrapText,
 } from "./utils";
@@ -105,7 +106,7 @@ export function calendar(
 	game.ctx.textAlign = "center";
 	game.ctx.textBaseline = "middle";
 
-	const today = getDaysSinceEpoch();
+	const today = getToday();
 	const firstDays = getDaysSinceEpoch(first);
 
 	game.ctx.fillStyle = COLORS.fg(game);
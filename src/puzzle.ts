@@ -1,14 +1,25 @@
 import { DEBUG, type Game } from "./main";
 import {
-	getDaysSinceEpoch,
+	getToday,
 	isPangram,
 	removeAccents,
 	scoreWord,
 } from "./utils";
-import { dailyPuzzle } from "../puzzle-generator/pkg/";
+import {
+	dailyPuzzle,
+	puzzleFromScheduleEntry,
+} from "../puzzle-generator/pkg/";
 
 export type WordMap = { [key: string]: string[] };
 
+export interface WordInfo {
+	category: string;
+	frequency: number;
+	lemma_frequency: number;
+	// From 0 (easy) to 1 (hard)
+	difficulty: number;
+}
+
 export interface Puzzle {
 	letters: string[];
 	words: WordMap;
@@ -16,6 +27,8 @@ export interface Puzzle {
 	forms: WordMap;
 	pangrams: string[];
 	day: number;
+	// Missing from puzzles saved before word metadata was generated
+	info?: { [key: string]: WordInfo };
 
 	maxScore: number;
 	word: string;
@@ -26,7 +39,7 @@ export interface Puzzle {
 
 export type PuzzleData = Pick<
 	Puzzle,
-	"letters" | "words" | "lemmas" | "forms" | "pangrams" | "day"
+	"letters" | "words" | "lemmas" | "forms" | "pangrams" | "day" | "info"
 >;
 
 export interface HintsData {
@@ -41,8 +54,24 @@ export interface SerializableHintsData {
 	starts: [string, number][];
 }
 
+/** An error thrown by the puzzle generator */
+export interface PuzzleError {
+	code: string;
+	message: string;
+	details?: { [key: string]: unknown };
+}
+
+export function isPuzzleError(error: unknown): error is PuzzleError {
+	return (
+		typeof error === "object" &&
+		error != null &&
+		"code" in error &&
+		"message" in error
+	);
+}
+
 export async function getPuzzle(game: Game, day?: number) {
-	const today = getDaysSinceEpoch();
+	const today = getToday();
 	if (day == null) {
 		// If the day is not specified use the URL parameter, or default to today
 		const params = new URLSearchParams(window.location.search);
@@ -77,14 +106,18 @@ export async function getPuzzle(game: Game, day?: number) {
 
 	// Try to create the puzzle
 	let puzzle;
+	let error: unknown;
 	try {
 		puzzle = await createDailyPuzzle(day);
-	} catch (error) {
+	} catch (err) {
+		error = err;
 		console.error("Error creating daily puzzle:");
 		console.error(error);
 	}
 	if (puzzle == null) {
-		game.errorText = "Failed to create new puzzle";
+		game.errorText = isPuzzleError(error)
+			? `Failed to create new puzzle: ${error.message}`
+			: "Failed to create new puzzle";
 		return;
 	}
 
@@ -128,9 +161,41 @@ async function loadPuzzle(day: number): Promise<{
 	return { puzzle, hintsPuzzle, hintsFound };
 }
 
+/**
+ * Build the day's puzzle from the schedule, if there is one and it has the
+ * day, otherwise null
+ */
+async function scheduledPuzzle(day: number): Promise<PuzzleData | null> {
+	try {
+		const response = await fetch("schedule.json");
+		if (!response.ok) {
+			return null;
+		}
+		const entries = (await response.json()) as { day: number }[];
+		const entry = entries.find((entry) => entry.day === day);
+		if (entry == null) {
+			return null;
+		}
+		return JSON.parse(puzzleFromScheduleEntry(JSON.stringify(entry)));
+	} catch (error) {
+		if (isPuzzleError(error) && error.code === "version-mismatch") {
+			console.warn(
+				`\tThe schedule for day ${day} is for another word list:`,
+				error.details,
+			);
+		} else {
+			console.warn(`\tCould not use the schedule for day ${day}:`, error);
+		}
+		return null;
+	}
+}
+
 async function createDailyPuzzle(day: number): Promise<Puzzle | null> {
-	console.log(`\tGenerating puzzle for day ${day} with WASM...`);
-	const puzzle = JSON.parse(await dailyPuzzle(day)) as PuzzleData;
+	let puzzle = await scheduledPuzzle(day);
+	if (puzzle == null) {
+		console.log(`\tGenerating puzzle for day ${day} with WASM...`);
+		puzzle = JSON.parse(await dailyPuzzle(day)) as PuzzleData;
+	}
 	console.log(puzzle);
 	console.log("\tPuzzle generated");
 
@@ -142,6 +207,7 @@ async function createDailyPuzzle(day: number): Promise<Puzzle | null> {
 		forms: puzzle.forms,
 		pangrams: puzzle.pangrams,
 		day: puzzle.day,
+		info: puzzle.info,
 		maxScore: Object.values(puzzle.words)
 			.flat()
 			.reduce((sum, word) => sum + scoreWord(word, puzzle.pangrams), 0),
@@ -1,4 +1,5 @@
 import { main, type Game } from "./main";
+import { puzzleDayFor } from "../puzzle-generator/pkg/";
 
 /**
  * Resizes the canvas to the specified height and width, and returns the the scaling and scaled dimensions.
@@ -269,6 +270,20 @@ export function scrolling(
 	return [newScroll, newScrollSpeed];
 }
 
+/**
+ * Get today's puzzle day, which starts at midnight in the player's time zone.
+ * Falls back to UTC if the time zone is not known to the puzzle generator.
+ */
+export function getToday(): number {
+	const zone = Intl.DateTimeFormat().resolvedOptions().timeZone;
+	try {
+		return puzzleDayFor(Date.now(), zone);
+	} catch (error) {
+		console.log(`Unknown time zone ${zone}, using UTC`, error);
+		return puzzleDayFor(Date.now());
+	}
+}
+
 /**
  * Get the number of days since the epoch for this date.
  */
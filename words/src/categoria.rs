@@ -0,0 +1,132 @@
+use unidecode::unidecode;
+
+/// Part of speech of a word, using the CREA's categories
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum Categoria {
+    /// Adjetivo
+    A,
+    /// Adverbio
+    R,
+    /// Afijo
+    J,
+    /// Artículo
+    T,
+    /// Conjunción
+    C,
+    /// Contracción
+    E,
+    /// Cuantificador
+    Q,
+    /// Demostrativo
+    D,
+    /// Desconocido
+    U,
+    /// Extranjerismo
+    F,
+    /// Interjección
+    I,
+    /// Interrogativo
+    W,
+    /// Numeral
+    M,
+    /// Posesivo
+    X,
+    /// Preposición
+    P,
+    /// Pronombre personal
+    L,
+    /// Puntación
+    Y,
+    /// Relativo
+    H,
+    /// Sustantivo
+    N,
+    /// Verbo
+    V,
+}
+
+impl Categoria {
+    /// Parse a part of speech tag as used by other corpora: a CREA code (`V`),
+    /// a Spanish category name (`Verbo`), or a Universal Dependencies tag
+    /// (`VERB`)
+    pub fn from_tag(tag: &str) -> Option<Categoria> {
+        let tag = unidecode(tag.trim()).to_lowercase();
+        let categoria = match tag.as_str() {
+            "a" | "adjetivo" | "adj" => Categoria::A,
+            "r" | "adverbio" | "adv" => Categoria::R,
+            "j" | "afijo" => Categoria::J,
+            "t" | "articulo" | "det" => Categoria::T,
+            "c" | "conjuncion" | "cconj" | "sconj" => Categoria::C,
+            "e" | "contraccion" => Categoria::E,
+            "q" | "cuantificador" => Categoria::Q,
+            "d" | "demostrativo" => Categoria::D,
+            "u" | "desconocido" => Categoria::U,
+            "f" | "extranjerismo" => Categoria::F,
+            "i" | "interjeccion" | "intj" => Categoria::I,
+            "w" | "interrogativo" => Categoria::W,
+            "m" | "numeral" | "num" => Categoria::M,
+            "x" | "posesivo" => Categoria::X,
+            "p" | "preposicion" | "adp" => Categoria::P,
+            "l" | "pronombre personal" | "pronombre" | "pron" => Categoria::L,
+            "y" | "puntuacion" | "puntacion" | "punct" | "sym" => Categoria::Y,
+            "h" | "relativo" => Categoria::H,
+            "n" | "sustantivo" | "noun" | "propn" => Categoria::N,
+            "v" | "verbo" | "verb" | "aux" => Categoria::V,
+            _ => return None,
+        };
+        Some(categoria)
+    }
+
+    /// Spanish name of the category, e.g. "verbo"
+    pub fn nombre(&self) -> &'static str {
+        match self {
+            Categoria::A => "adjetivo",
+            Categoria::R => "adverbio",
+            Categoria::J => "afijo",
+            Categoria::T => "artículo",
+            Categoria::C => "conjunción",
+            Categoria::E => "contracción",
+            Categoria::Q => "cuantificador",
+            Categoria::D => "demostrativo",
+            Categoria::U => "desconocido",
+            Categoria::F => "extranjerismo",
+            Categoria::I => "interjección",
+            Categoria::W => "interrogativo",
+            Categoria::M => "numeral",
+            Categoria::X => "posesivo",
+            Categoria::P => "preposición",
+            Categoria::L => "pronombre personal",
+            Categoria::Y => "puntuación",
+            Categoria::H => "relativo",
+            Categoria::N => "sustantivo",
+            Categoria::V => "verbo",
+        }
+    }
+
+    /// English name of the category
+    pub fn name(&self) -> &'static str {
+        match self {
+            Categoria::A => "adjective",
+            Categoria::R => "adverb",
+            Categoria::J => "affix",
+            Categoria::T => "article",
+            Categoria::C => "conjunction",
+            Categoria::E => "contraction",
+            Categoria::Q => "quantifier",
+            Categoria::D => "demonstrative",
+            Categoria::U => "unknown",
+            Categoria::F => "foreign word",
+            Categoria::I => "interjection",
+            Categoria::W => "interrogative",
+            Categoria::M => "numeral",
+            Categoria::X => "possessive",
+            Categoria::P => "preposition",
+            Categoria::L => "personal pronoun",
+            Categoria::Y => "punctuation",
+            Categoria::H => "relative",
+            Categoria::N => "noun",
+            Categoria::V => "verb",
+        }
+    }
+}
@@ -0,0 +1,12 @@
+/// Why a word of the corpus was left out of the word list
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum Exclusion {
+    /// Too infrequent in the corpus
+    Rare,
+    /// A foreign word
+    Foreign,
+    /// A proper noun, or a form of one
+    ProperNoun,
+}
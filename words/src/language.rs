@@ -0,0 +1,80 @@
+use unidecode::unidecode;
+
+/// Languages the game's word lists can be generated for
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Language {
+    /// Spanish
+    #[default]
+    Es,
+    /// Portuguese
+    Pt,
+    /// Catalan
+    Ca,
+    /// English
+    En,
+}
+
+impl Language {
+    pub const ALL: [Language; 4] = [Language::Es, Language::Pt, Language::Ca, Language::En];
+
+    /// ISO 639-1 code of the language
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::Es => "es",
+            Language::Pt => "pt",
+            Language::Ca => "ca",
+            Language::En => "en",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Language> {
+        Language::ALL
+            .into_iter()
+            .find(|language| language.code() == code.trim().to_lowercase())
+    }
+
+    /// All the lowercase characters a word may be written with
+    pub fn alphabet(&self) -> &'static str {
+        match self {
+            Language::Es => "abcdefghijklmnopqrstuvwxyzáéíóúüñ",
+            Language::Pt => "abcdefghijklmnopqrstuvwxyzáâãàçéêíóôõúü",
+            Language::Ca => "abcdefghijklmnopqrstuvwxyzàçèéíïòóúü·",
+            Language::En => "abcdefghijklmnopqrstuvwxyz",
+        }
+    }
+
+    /// Whether the word is written using only this language's alphabet
+    pub fn is_written_in(&self, word: &str) -> bool {
+        let alphabet = self.alphabet();
+        !word.is_empty() && word.chars().all(|c| alphabet.contains(c))
+    }
+
+    /// Normalize a word to the letters used in the puzzle, e.g. removing
+    /// accents so that "papá" is played as "papa"
+    pub fn normalize(&self, word: &str) -> String {
+        match self {
+            // The punt volat of "l·l" is not a letter
+            Language::Ca => unidecode(&word.replace('·', "")),
+            Language::Es | Language::Pt | Language::En => unidecode(word),
+        }
+    }
+}
+
+impl std::fmt::Display for Language {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+impl std::str::FromStr for Language {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Language::from_code(s).ok_or_else(|| format!("unknown language {:?}", s))
+    }
+}
@@ -1 +1,128 @@
+pub mod categoria;
+pub mod exclusion;
+pub mod language;
 pub mod palabras;
+
+use std::sync::OnceLock;
+
+pub use categoria::Categoria;
+pub use exclusion::Exclusion;
+pub use language::Language;
+
+/// A generated word list and its common pangrams
+#[derive(Clone, Copy)]
+pub struct Dictionary {
+    pub language: Language,
+    /// Regional variant the word list was built for, e.g. `general`, `spain`,
+    /// or `pan-hispanic:3`
+    pub variant: &'static str,
+    /// (form, lemma) pairs, sorted
+    pub words: &'static [(&'static str, &'static str)],
+    /// Normalized common pangrams, sorted
+    pub pangrams: &'static [&'static str],
+    /// (category, normalized frequency, lemma normalized frequency,
+    /// difficulty) of each of the words, in the same order. Empty if the word
+    /// list was generated without metadata.
+    pub metadata: &'static [(Categoria, f32, f32, f32)],
+    /// (normalized form, form, reason) of words of the corpus left out of the
+    /// word list that could otherwise be played, sorted. Empty if the word list
+    /// was generated without them.
+    pub excluded: &'static [(&'static str, &'static str, Exclusion)],
+    /// Version of the word list, see [Dictionary::hash_version]
+    pub version: &'static str,
+}
+
+/// Corpus information about a word
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct WordInfo {
+    pub category: Categoria,
+    /// Occurrences per million words
+    pub frequency: f32,
+    /// Occurrences per million words of all of the lemma's forms
+    pub lemma_frequency: f32,
+    /// How hard the word is to find, from 0 (easy) to 1 (hard)
+    pub difficulty: f32,
+}
+
+impl Dictionary {
+    /// Index of a (form, lemma) pair in the word list
+    pub fn position(&self, form: &str, lemma: &str) -> Option<usize> {
+        self.words.binary_search(&(form, lemma)).ok()
+    }
+
+    /// Whether the word list was generated with metadata. Word lists
+    /// generated before metadata have an empty table, and no word has info.
+    pub fn has_metadata(&self) -> bool {
+        !self.metadata.is_empty()
+    }
+
+    pub fn info(&self, form: &str, lemma: &str) -> Option<WordInfo> {
+        let (category, frequency, lemma_frequency, difficulty) =
+            *self.metadata.get(self.position(form, lemma)?)?;
+        Some(WordInfo {
+            category,
+            frequency,
+            lemma_frequency,
+            difficulty,
+        })
+    }
+
+    /// The forms of a normalized word that were left out of the word list,
+    /// and why
+    pub fn excluded(&self, normalized: &str) -> &'static [(&'static str, &'static str, Exclusion)] {
+        let start = self
+            .excluded
+            .partition_point(|(word, _, _)| *word < normalized);
+        let end = self
+            .excluded
+            .partition_point(|(word, _, _)| *word <= normalized);
+        &self.excluded[start..end]
+    }
+
+    /// Version of the word list, e.g. `es-general-1a2b3c4d`, changing whenever
+    /// its words or pangrams change. Anything derived from the word list, like
+    /// a puzzle schedule, can be checked against it. It hashes every word, so
+    /// [dictionary] only computes it once and keeps it as the `version`.
+    pub fn hash_version(&self) -> String {
+        // FNV-1a, so that the version is stable across builds and platforms
+        let mut hash: u32 = 0x811c9dc5;
+        let mut add = |s: &str| {
+            s.bytes().chain([0]).for_each(|byte| {
+                hash ^= byte as u32;
+                hash = hash.wrapping_mul(0x01000193);
+            });
+        };
+        self.words.iter().for_each(|(form, lemma)| {
+            add(form);
+            add(lemma);
+        });
+        self.pangrams.iter().for_each(|pangram| add(pangram));
+        format!("{}-{}-{:08x}", self.language.code(), self.variant, hash)
+    }
+}
+
+/// Get the generated dictionary for a language, if one has been generated.
+/// `crea-explorer generate --language <code>` writes `palabras_<code>.rs`,
+/// which must be added here as a module to be used.
+pub fn dictionary(language: Language) -> Option<Dictionary> {
+    static ES_VERSION: OnceLock<String> = OnceLock::new();
+
+    let (mut dictionary, version) = match language {
+        Language::Es => (
+            Dictionary {
+                language,
+                variant: palabras::VARIANT,
+                words: palabras::PALABRAS,
+                pangrams: palabras::PANGRAMS,
+                metadata: palabras::METADATA,
+                excluded: palabras::EXCLUIDAS,
+                version: "",
+            },
+            &ES_VERSION,
+        ),
+        _ => return None,
+    };
+    dictionary.version = version.get_or_init(|| dictionary.hash_version());
+    Some(dictionary)
+}
@@ -1,3 +1,7 @@
+use crate::{Categoria, Exclusion};
+
+pub const VARIANT: &str = "general";
+
 pub const PALABRAS: &[(&str, &str); 104244] = &[
     ("aaaah", "ah"),
     ("aaah", "ah"),
@@ -105935,3 +105939,9 @@ pub const PANGRAMS: &[&str; 1688] = &[
     "vuestra",
     "vuestro",
 ];
+
+pub const METADATA: &[(Categoria, f32, f32, f32); 0] = &[
+];
+
+pub const EXCLUIDAS: &[(&str, &str, Exclusion); 0] = &[
+];